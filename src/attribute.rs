@@ -0,0 +1,528 @@
+use crate::datatypes;
+use crate::error;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttrValueType {
+    Int,
+    Float,
+    Bool,
+    Str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttrUnit {
+    None,
+    Hz,
+    Db,
+    MilliCelsius,
+}
+
+impl AttrUnit {
+    pub fn get_symbol(&self) -> &'static str {
+        match self {
+            Self::None => "",
+            Self::Hz => "Hz",
+            Self::Db => "dB",
+            Self::MilliCelsius => "m°C",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttrValue {
+    Int(i128),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+}
+
+impl AttrValue {
+    pub fn parse(value_type: AttrValueType, value: &str) -> Result<AttrValue, ()> {
+        match value_type {
+            AttrValueType::Int => parse_int(value).map(AttrValue::Int),
+            AttrValueType::Float => parse_float(value).map(AttrValue::Float),
+            AttrValueType::Bool => Ok(AttrValue::Bool(parse_int(value)? != 0)),
+            AttrValueType::Str => Ok(AttrValue::Str(value.trim().to_owned())),
+        }
+    }
+
+    pub fn get_string(&self) -> String {
+        match self {
+            Self::Int(i) => i.to_string(),
+            Self::Float(f) => f.to_string(),
+            Self::Bool(b) => (if *b { "1" } else { "0" }).to_owned(),
+            Self::Str(s) => s.clone(),
+        }
+    }
+
+    pub fn get_value_type(&self) -> AttrValueType {
+        match self {
+            Self::Int(_) => AttrValueType::Int,
+            Self::Float(_) => AttrValueType::Float,
+            Self::Bool(_) => AttrValueType::Bool,
+            Self::Str(_) => AttrValueType::Str,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Int(i) => Some(*i as f64),
+            Self::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+}
+
+// Numeric attributes may carry a unit suffix, as in "-10.000000 dB"
+fn first_token(value: &str) -> Result<&str, ()> {
+    value.split_whitespace().next().ok_or(())
+}
+
+// Fractional values are an error, read them with parse_float instead
+pub fn parse_int(value: &str) -> Result<i128, ()> {
+    first_token(value)?.parse::<i128>().map_err(|_| ())
+}
+
+pub fn parse_float(value: &str) -> Result<f64, ()> {
+    first_token(value)?.parse::<f64>().map_err(|_| ())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttrAvailable {
+    Range(datatypes::AttrRange),
+    List(Vec<String>),
+}
+
+impl AttrAvailable {
+    // "[min step max]" is a range, anything else a list of accepted values
+    pub fn parse(value: &str) -> AttrAvailable {
+        let value = value.trim();
+        if value.starts_with('[')
+            && let Ok(range) = datatypes::AttrRange::parse(value)
+        {
+            return AttrAvailable::Range(range);
+        }
+        AttrAvailable::List(
+            value
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .split_whitespace()
+                .map(|f| f.to_owned())
+                .collect(),
+        )
+    }
+
+    pub fn check(&self, value: &AttrValue) -> Result<(), error::AdiError> {
+        let accepted = match self {
+            Self::Range(range) => {
+                // Ranges only bound numbers, a string or a bool is the wrong type
+                let number = value.as_f64().ok_or(error::AdiError::TypeMismatchError {
+                    expected: AttrValueType::Float,
+                    found: value.get_value_type(),
+                })?;
+                return range.check(number);
+            }
+            Self::List(list) => match value.as_f64() {
+                Some(number) => list
+                    .iter()
+                    .any(|f| parse_float(f).is_ok_and(|f| f == number)),
+                None => list.contains(&value.get_string()),
+            },
+        };
+        if accepted {
+            Ok(())
+        } else {
+            Err(error::AdiError::RangeError {
+                value: value.get_string(),
+                available: self.to_string(),
+            })
+        }
+    }
+}
+
+impl std::fmt::Display for AttrAvailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Range(range) => write!(f, "{}", range),
+            Self::List(list) => write!(f, "{}", list.join(" ")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttrDescriptor {
+    pub name: &'static str,
+    // None for device attributes
+    pub channel_name: Option<&'static str>,
+    pub output: bool,
+    pub attr_name: &'static str,
+    pub value_type: AttrValueType,
+    pub unit: AttrUnit,
+    // Sibling attribute listing the accepted values
+    pub available: Option<&'static str>,
+}
+
+const fn channel(
+    name: &'static str,
+    channel_name: &'static str,
+    output: bool,
+    attr_name: &'static str,
+    value_type: AttrValueType,
+    unit: AttrUnit,
+    available: Option<&'static str>,
+) -> AttrDescriptor {
+    AttrDescriptor {
+        name,
+        channel_name: Some(channel_name),
+        output,
+        attr_name,
+        value_type,
+        unit,
+        available,
+    }
+}
+
+const fn device(
+    name: &'static str,
+    attr_name: &'static str,
+    value_type: AttrValueType,
+    unit: AttrUnit,
+    available: Option<&'static str>,
+) -> AttrDescriptor {
+    AttrDescriptor {
+        name,
+        channel_name: None,
+        output: false,
+        attr_name,
+        value_type,
+        unit,
+        available,
+    }
+}
+
+pub static AD9361_ATTRIBUTES: &[AttrDescriptor] = &[
+    channel(
+        "rx_lo",
+        "altvoltage0",
+        true,
+        "frequency",
+        AttrValueType::Int,
+        AttrUnit::Hz,
+        Some("frequency_available"),
+    ),
+    channel(
+        "tx_lo",
+        "altvoltage1",
+        true,
+        "frequency",
+        AttrValueType::Int,
+        AttrUnit::Hz,
+        Some("frequency_available"),
+    ),
+    channel(
+        "rx_sample_rate",
+        "voltage0",
+        false,
+        "sampling_frequency",
+        AttrValueType::Int,
+        AttrUnit::Hz,
+        Some("sampling_frequency_available"),
+    ),
+    channel(
+        "tx_sample_rate",
+        "voltage0",
+        true,
+        "sampling_frequency",
+        AttrValueType::Int,
+        AttrUnit::Hz,
+        Some("sampling_frequency_available"),
+    ),
+    channel(
+        "rx_rf_bandwidth",
+        "voltage0",
+        false,
+        "rf_bandwidth",
+        AttrValueType::Int,
+        AttrUnit::Hz,
+        Some("rf_bandwidth_available"),
+    ),
+    channel(
+        "tx_rf_bandwidth",
+        "voltage0",
+        true,
+        "rf_bandwidth",
+        AttrValueType::Int,
+        AttrUnit::Hz,
+        Some("rf_bandwidth_available"),
+    ),
+    channel(
+        "rx_hardwaregain_chan0",
+        "voltage0",
+        false,
+        "hardwaregain",
+        AttrValueType::Float,
+        AttrUnit::Db,
+        Some("hardwaregain_available"),
+    ),
+    channel(
+        "rx_hardwaregain_chan1",
+        "voltage1",
+        false,
+        "hardwaregain",
+        AttrValueType::Float,
+        AttrUnit::Db,
+        Some("hardwaregain_available"),
+    ),
+    channel(
+        "tx_hardwaregain_chan0",
+        "voltage0",
+        true,
+        "hardwaregain",
+        AttrValueType::Float,
+        AttrUnit::Db,
+        Some("hardwaregain_available"),
+    ),
+    channel(
+        "tx_hardwaregain_chan1",
+        "voltage1",
+        true,
+        "hardwaregain",
+        AttrValueType::Float,
+        AttrUnit::Db,
+        Some("hardwaregain_available"),
+    ),
+    channel(
+        "gain_control_mode_chan0",
+        "voltage0",
+        false,
+        "gain_control_mode",
+        AttrValueType::Str,
+        AttrUnit::None,
+        Some("gain_control_mode_available"),
+    ),
+    channel(
+        "gain_control_mode_chan1",
+        "voltage1",
+        false,
+        "gain_control_mode",
+        AttrValueType::Str,
+        AttrUnit::None,
+        Some("gain_control_mode_available"),
+    ),
+    channel(
+        "rssi_chan0",
+        "voltage0",
+        false,
+        "rssi",
+        AttrValueType::Float,
+        AttrUnit::Db,
+        None,
+    ),
+    channel(
+        "rssi_chan1",
+        "voltage1",
+        false,
+        "rssi",
+        AttrValueType::Float,
+        AttrUnit::Db,
+        None,
+    ),
+    channel(
+        "rx_rf_port_select",
+        "voltage0",
+        false,
+        "rf_port_select",
+        AttrValueType::Str,
+        AttrUnit::None,
+        Some("rf_port_select_available"),
+    ),
+    channel(
+        "tx_rf_port_select",
+        "voltage0",
+        true,
+        "rf_port_select",
+        AttrValueType::Str,
+        AttrUnit::None,
+        Some("rf_port_select_available"),
+    ),
+    channel(
+        "quadrature_tracking_en",
+        "voltage0",
+        false,
+        "quadrature_tracking_en",
+        AttrValueType::Bool,
+        AttrUnit::None,
+        None,
+    ),
+    channel(
+        "rf_dc_offset_tracking_en",
+        "voltage0",
+        false,
+        "rf_dc_offset_tracking_en",
+        AttrValueType::Bool,
+        AttrUnit::None,
+        None,
+    ),
+    channel(
+        "bb_dc_offset_tracking_en",
+        "voltage0",
+        false,
+        "bb_dc_offset_tracking_en",
+        AttrValueType::Bool,
+        AttrUnit::None,
+        None,
+    ),
+    channel(
+        "fir_enable",
+        "out",
+        false,
+        "voltage_filter_fir_en",
+        AttrValueType::Bool,
+        AttrUnit::None,
+        None,
+    ),
+    channel(
+        "temperature",
+        "temp0",
+        false,
+        "input",
+        AttrValueType::Int,
+        AttrUnit::MilliCelsius,
+        None,
+    ),
+    device(
+        "ensm_mode",
+        "ensm_mode",
+        AttrValueType::Str,
+        AttrUnit::None,
+        Some("ensm_mode_available"),
+    ),
+    device(
+        "calib_mode",
+        "calib_mode",
+        AttrValueType::Str,
+        AttrUnit::None,
+        Some("calib_mode_available"),
+    ),
+    device(
+        "xo_correction",
+        "xo_correction",
+        AttrValueType::Int,
+        AttrUnit::Hz,
+        Some("xo_correction_available"),
+    ),
+];
+
+pub fn find_attribute(name: &str) -> Option<&'static AttrDescriptor> {
+    AD9361_ATTRIBUTES.iter().find(|f| f.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_int_values() {
+        assert_eq!(parse_int("30720000"), Ok(30_720_000));
+        assert_eq!(parse_int(" -42\n"), Ok(-42));
+        assert_eq!(parse_int("+7"), Ok(7));
+        assert_eq!(parse_int("45000 m°C"), Ok(45_000));
+        assert_eq!(parse_int("1.5"), Err(()));
+        assert_eq!(parse_int("1e3"), Err(()));
+        assert_eq!(parse_int("manual"), Err(()));
+        assert_eq!(parse_int(""), Err(()));
+    }
+
+    #[test]
+    fn parse_float_values() {
+        assert_eq!(parse_float("-10.000000 dB"), Ok(-10.0));
+        assert_eq!(parse_float("71.75"), Ok(71.75));
+        assert_eq!(parse_float("-0.25"), Ok(-0.25));
+        assert_eq!(parse_float("2.5e6"), Ok(2_500_000.0));
+        assert_eq!(parse_float("30720000"), Ok(30_720_000.0));
+        assert_eq!(parse_float("dB"), Err(()));
+        assert_eq!(parse_float("  "), Err(()));
+    }
+
+    #[test]
+    fn available_range() {
+        let available = AttrAvailable::parse("[-3 1 71]\n");
+        assert_eq!(
+            available,
+            AttrAvailable::Range(datatypes::AttrRange {
+                min: -3.0,
+                step: 1.0,
+                max: 71.0,
+            })
+        );
+        assert!(available.check(&AttrValue::Float(10.0)).is_ok());
+        assert!(matches!(
+            available.check(&AttrValue::Float(10.5)),
+            Err(error::AdiError::RangeError { .. })
+        ));
+        assert_eq!(
+            available.check(&AttrValue::Int(72)),
+            Err(error::AdiError::RangeError {
+                value: "72".to_owned(),
+                available: available.to_string(),
+            })
+        );
+        assert_eq!(
+            available.check(&AttrValue::Str("10".to_owned())),
+            Err(error::AdiError::TypeMismatchError {
+                expected: AttrValueType::Float,
+                found: AttrValueType::Str,
+            })
+        );
+        assert_eq!(
+            available.check(&AttrValue::Bool(true)),
+            Err(error::AdiError::TypeMismatchError {
+                expected: AttrValueType::Float,
+                found: AttrValueType::Bool,
+            })
+        );
+    }
+
+    #[test]
+    fn available_list() {
+        let available = AttrAvailable::parse("manual fast_attack slow_attack hybrid");
+        assert_eq!(
+            available,
+            AttrAvailable::List(vec![
+                "manual".to_owned(),
+                "fast_attack".to_owned(),
+                "slow_attack".to_owned(),
+                "hybrid".to_owned(),
+            ])
+        );
+        assert!(
+            available
+                .check(&AttrValue::Str("hybrid".to_owned()))
+                .is_ok()
+        );
+        assert_eq!(
+            available.check(&AttrValue::Str("auto".to_owned())),
+            Err(error::AdiError::RangeError {
+                value: "auto".to_owned(),
+                available: "manual fast_attack slow_attack hybrid".to_owned(),
+            })
+        );
+
+        // Bracketed lists which are not ranges stay lists, numbers compare by value
+        let rates = AttrAvailable::parse("[30720000 3840000]");
+        assert_eq!(
+            rates,
+            AttrAvailable::List(vec!["30720000".to_owned(), "3840000".to_owned()])
+        );
+        assert!(rates.check(&AttrValue::Int(3_840_000)).is_ok());
+        assert!(rates.check(&AttrValue::Float(3_840_000.0)).is_ok());
+        assert!(matches!(
+            rates.check(&AttrValue::Int(1_000_000)),
+            Err(error::AdiError::RangeError { .. })
+        ));
+        assert!(matches!(
+            rates.check(&AttrValue::Str("fast".to_owned())),
+            Err(error::AdiError::RangeError { .. })
+        ));
+    }
+}
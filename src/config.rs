@@ -0,0 +1,291 @@
+use crate::datatypes;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+}
+
+impl ConfigFormat {
+    pub fn from_filename(filename: &str) -> Result<ConfigFormat, ()> {
+        match std::path::Path::new(filename)
+            .extension()
+            .and_then(|f| f.to_str())
+        {
+            Some("toml") => Ok(Self::Toml),
+            Some("json") => Ok(Self::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelConfig {
+    pub gain_control_mode: datatypes::GainControlMode,
+    pub rx_hardwaregain: f64,
+    pub tx_hardwaregain: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CalibrationConfig {
+    pub quadrature_tracking: bool,
+    pub rf_dc_offset_tracking: bool,
+    pub bb_dc_offset_tracking: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DdsConfig {
+    pub frequencies: Vec<u64>,
+    pub scales: Vec<f64>,
+    // Millidegrees
+    pub phases: Vec<u32>,
+    pub enabled: Vec<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RadioConfig {
+    pub rx_lo: u64,
+    pub tx_lo: u64,
+    pub sample_rate: u32,
+    pub rx_rf_bandwidth: u32,
+    pub tx_rf_bandwidth: u32,
+    pub rx_port: datatypes::RxPort,
+    pub tx_port: datatypes::TxPort,
+    pub rx_enabled_channels: Vec<u32>,
+    pub tx_enabled_channels: Vec<u32>,
+    // .ftr contents of the loaded filter, absent when the driver FIR is disabled
+    pub fir_filter: Option<String>,
+    pub calibration: CalibrationConfig,
+    pub channels: Vec<ChannelConfig>,
+    pub dds: DdsConfig,
+    // Settings the snapshot could not capture, not compared by diff
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub skipped: Vec<String>,
+}
+
+// Read-back tolerances: the driver rounds rates to its clock chain, gains to
+// the gain table step and DDS values to the NCO resolution
+fn close(a: f64, b: f64, tolerance: f64) -> bool {
+    (a - b).abs() <= tolerance
+}
+
+fn close_rate(a: f64, b: f64) -> bool {
+    close(a, b, (a.abs() * 1e-5).max(10.0))
+}
+
+#[cfg(feature = "serde")]
+impl RadioConfig {
+    pub fn to_toml(&self) -> Result<String, ()> {
+        toml::to_string(self).map_err(|_| ())
+    }
+
+    pub fn from_toml(data: &str) -> Result<RadioConfig, ()> {
+        toml::from_str(data).map_err(|_| ())
+    }
+
+    pub fn to_json(&self) -> Result<String, ()> {
+        serde_json::to_string_pretty(self).map_err(|_| ())
+    }
+
+    pub fn from_json(data: &str) -> Result<RadioConfig, ()> {
+        serde_json::from_str(data).map_err(|_| ())
+    }
+
+    pub fn load(filename: &str) -> Result<RadioConfig, ()> {
+        let data = std::fs::read_to_string(filename).map_err(|_| ())?;
+        match ConfigFormat::from_filename(filename)? {
+            ConfigFormat::Toml => RadioConfig::from_toml(data.as_str()),
+            ConfigFormat::Json => RadioConfig::from_json(data.as_str()),
+        }
+    }
+
+    pub fn save(&self, filename: &str) -> Result<(), ()> {
+        let data = match ConfigFormat::from_filename(filename)? {
+            ConfigFormat::Toml => self.to_toml()?,
+            ConfigFormat::Json => self.to_json()?,
+        };
+        std::fs::write(filename, data).map_err(|_| ())
+    }
+}
+
+impl RadioConfig {
+    // Names of the settings whose read-back does not match this configuration
+    pub fn diff(&self, other: &RadioConfig) -> Vec<String> {
+        let mut diffs = vec![];
+        let mut check = |name: &str, equal: bool| {
+            if !equal {
+                diffs.push(name.to_owned());
+            }
+        };
+        check("rx_lo", close_rate(self.rx_lo as f64, other.rx_lo as f64));
+        check("tx_lo", close_rate(self.tx_lo as f64, other.tx_lo as f64));
+        check(
+            "sample_rate",
+            close_rate(self.sample_rate as f64, other.sample_rate as f64),
+        );
+        check(
+            "rx_rf_bandwidth",
+            close_rate(self.rx_rf_bandwidth as f64, other.rx_rf_bandwidth as f64),
+        );
+        check(
+            "tx_rf_bandwidth",
+            close_rate(self.tx_rf_bandwidth as f64, other.tx_rf_bandwidth as f64),
+        );
+        check("rx_port", self.rx_port == other.rx_port);
+        check("tx_port", self.tx_port == other.tx_port);
+        check(
+            "rx_enabled_channels",
+            self.rx_enabled_channels == other.rx_enabled_channels,
+        );
+        check(
+            "tx_enabled_channels",
+            self.tx_enabled_channels == other.tx_enabled_channels,
+        );
+        // Without a recorded filter any filter the driver ends up with is accepted
+        check(
+            "fir_filter",
+            self.fir_filter.is_none() || self.fir_filter == other.fir_filter,
+        );
+        check("calibration", self.calibration == other.calibration);
+        check(
+            "channels",
+            self.channels.len() == other.channels.len()
+                && self
+                    .channels
+                    .iter()
+                    .zip(other.channels.iter())
+                    .all(|(a, b)| {
+                        a.gain_control_mode == b.gain_control_mode
+                        // The AGC owns the RX gain outside manual mode
+                        && (a.gain_control_mode != datatypes::GainControlMode::Manual
+                            || close(a.rx_hardwaregain, b.rx_hardwaregain, 0.5))
+                        && close(a.tx_hardwaregain, b.tx_hardwaregain, 0.5)
+                    }),
+        );
+        check(
+            "dds",
+            self.dds.enabled == other.dds.enabled
+                && self.dds.frequencies.len() == other.dds.frequencies.len()
+                && std::iter::zip(&self.dds.frequencies, &other.dds.frequencies)
+                    .all(|(a, b)| close(*a as f64, *b as f64, 1000.0))
+                && std::iter::zip(&self.dds.scales, &other.dds.scales)
+                    .all(|(a, b)| close(*a, *b, 1e-3))
+                && std::iter::zip(&self.dds.phases, &other.dds.phases)
+                    .all(|(a, b)| close(*a as f64, *b as f64, 100.0)),
+        );
+        diffs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RadioConfig {
+        RadioConfig {
+            rx_lo: 2_400_000_000,
+            tx_lo: 2_450_000_000,
+            sample_rate: 1_000_000,
+            rx_rf_bandwidth: 18_000_000,
+            tx_rf_bandwidth: 18_000_000,
+            rx_port: datatypes::RxPort::ABalanced,
+            tx_port: datatypes::TxPort::A,
+            rx_enabled_channels: vec![0],
+            tx_enabled_channels: vec![0],
+            fir_filter: None,
+            calibration: CalibrationConfig {
+                quadrature_tracking: true,
+                rf_dc_offset_tracking: true,
+                bb_dc_offset_tracking: true,
+            },
+            channels: vec![ChannelConfig {
+                gain_control_mode: datatypes::GainControlMode::Manual,
+                rx_hardwaregain: 30.0,
+                tx_hardwaregain: -10.0,
+            }],
+            dds: DdsConfig {
+                frequencies: vec![100_000, 100_000],
+                scales: vec![0.5, 0.0],
+                phases: vec![90_000, 0],
+                enabled: vec![true, true],
+            },
+            skipped: vec![],
+        }
+    }
+
+    #[test]
+    fn diff_rate_tolerance() {
+        let expected = config();
+        let mut actual = config();
+        // 1e-5 of 2.4 GHz is 24 kHz
+        actual.rx_lo += 24_000;
+        // Small rates fall back to 10 Hz
+        actual.sample_rate += 10;
+        assert!(expected.diff(&actual).is_empty());
+
+        actual.rx_lo += 1;
+        actual.sample_rate += 1;
+        assert_eq!(expected.diff(&actual), ["rx_lo", "sample_rate"]);
+    }
+
+    #[test]
+    fn diff_gain_tolerance() {
+        let expected = config();
+        let mut actual = config();
+        actual.channels[0].rx_hardwaregain += 0.5;
+        actual.channels[0].tx_hardwaregain -= 0.5;
+        assert!(expected.diff(&actual).is_empty());
+
+        actual.channels[0].tx_hardwaregain -= 0.25;
+        assert_eq!(expected.diff(&actual), ["channels"]);
+
+        // The RX gain is only compared in manual mode
+        let mut expected = config();
+        expected.channels[0].gain_control_mode = datatypes::GainControlMode::SlowAttack;
+        let mut actual = expected.clone();
+        actual.channels[0].rx_hardwaregain = 71.0;
+        assert!(expected.diff(&actual).is_empty());
+        actual.channels.push(actual.channels[0].clone());
+        assert_eq!(expected.diff(&actual), ["channels"]);
+    }
+
+    #[test]
+    fn diff_dds_tolerance() {
+        let expected = config();
+        let mut actual = config();
+        actual.dds.frequencies[0] += 1000;
+        actual.dds.scales[0] += 0.000_9;
+        actual.dds.phases[0] -= 100;
+        assert!(expected.diff(&actual).is_empty());
+
+        for change in [
+            |f: &mut DdsConfig| f.frequencies[1] += 1001,
+            |f: &mut DdsConfig| f.scales[1] += 0.002,
+            |f: &mut DdsConfig| f.phases[1] += 101,
+            |f: &mut DdsConfig| f.enabled[1] = false,
+        ] {
+            let mut actual = config();
+            change(&mut actual.dds);
+            assert_eq!(expected.diff(&actual), ["dds"]);
+        }
+    }
+
+    #[test]
+    fn diff_fir_filter() {
+        let mut expected = config();
+        let mut actual = config();
+        actual.fir_filter = Some("RX 3 GAIN -6 DEC 4".to_owned());
+        // Without a recorded filter the driver's filter is accepted
+        assert!(expected.diff(&actual).is_empty());
+        expected.fir_filter = Some("RX 3 GAIN 0 DEC 4".to_owned());
+        assert_eq!(expected.diff(&actual), ["fir_filter"]);
+        // Skipped settings are a note on the snapshot, not a setting
+        let mut actual = config();
+        actual.skipped = vec!["fir_filter".to_owned()];
+        assert!(config().diff(&actual).is_empty());
+    }
+}
@@ -1,9 +1,31 @@
+use crate::error;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Loopback {
     Disable,
     Digital,
     RF,
 }
 
+impl Loopback {
+    pub fn get_value(&self) -> u32 {
+        match self {
+            Self::Disable => 0,
+            Self::Digital => 1,
+            Self::RF => 2,
+        }
+    }
+
+    pub fn from_value(value: u32) -> Result<Loopback, ()> {
+        match value {
+            0 => Ok(Self::Disable),
+            1 => Ok(Self::Digital),
+            2 => Ok(Self::RF),
+            _ => Err(()),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum SdrDataType {
     Int16,
@@ -18,3 +40,684 @@ impl SdrDataType {
 }
 
 pub type PlutoComplex = num::complex::Complex<f32>;
+
+pub trait TxSample: Copy {
+    // Number of 16-bit words one sample occupies in the TX buffer
+    const COMPONENTS: usize;
+    fn components(&self) -> [i16; 2];
+}
+
+impl TxSample for i16 {
+    const COMPONENTS: usize = 1;
+    fn components(&self) -> [i16; 2] {
+        [*self, 0]
+    }
+}
+
+impl TxSample for num::complex::Complex<i16> {
+    const COMPONENTS: usize = 2;
+    fn components(&self) -> [i16; 2] {
+        [self.re, self.im]
+    }
+}
+
+// Full scale is ±1.0, sent as ±16384 words. tx used to shift the samples
+// truncated to integers instead ((re as i128) << 14), which sent anything
+// between -1.0 and 1.0 as 0; whole values still give the same words
+impl TxSample for PlutoComplex {
+    const COMPONENTS: usize = 2;
+    fn components(&self) -> [i16; 2] {
+        [(self.re * 16384.0) as i16, (self.im * 16384.0) as i16]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TxFileFormat {
+    Cs16,
+    Cf32,
+    SigMF,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum GainControlMode {
+    Manual,
+    SlowAttack,
+    FastAttack,
+    Hybrid,
+}
+
+impl GainControlMode {
+    pub fn get_value(&self) -> &'static str {
+        match self {
+            Self::Manual => "manual",
+            Self::SlowAttack => "slow_attack",
+            Self::FastAttack => "fast_attack",
+            Self::Hybrid => "hybrid",
+        }
+    }
+
+    pub fn from_value(value: &str) -> Result<GainControlMode, ()> {
+        match value.trim() {
+            "manual" => Ok(Self::Manual),
+            "slow_attack" => Ok(Self::SlowAttack),
+            "fast_attack" => Ok(Self::FastAttack),
+            "hybrid" => Ok(Self::Hybrid),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CalibrationMode {
+    Auto,
+    Manual,
+    ManualTxQuad,
+    TxQuad,
+    RfDcOffs,
+    RssiGainStep,
+}
+
+impl CalibrationMode {
+    pub fn get_value(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Manual => "manual",
+            Self::ManualTxQuad => "manual_tx_quad",
+            Self::TxQuad => "tx_quad",
+            Self::RfDcOffs => "rf_dc_offs",
+            Self::RssiGainStep => "rssi_gain_step",
+        }
+    }
+
+    pub fn from_value(value: &str) -> Result<CalibrationMode, ()> {
+        match value.trim() {
+            "auto" => Ok(Self::Auto),
+            "manual" => Ok(Self::Manual),
+            "manual_tx_quad" => Ok(Self::ManualTxQuad),
+            "tx_quad" => Ok(Self::TxQuad),
+            "rf_dc_offs" => Ok(Self::RfDcOffs),
+            "rssi_gain_step" => Ok(Self::RssiGainStep),
+            _ => Err(()),
+        }
+    }
+
+    // Self-clearing bit of the calibration control register set while the calibration runs
+    pub fn get_calibration_field(&self) -> Option<&'static str> {
+        match self {
+            Self::Auto | Self::Manual => None,
+            Self::ManualTxQuad | Self::TxQuad => Some("tx_quad_cal"),
+            Self::RfDcOffs => Some("dc_cal_rf_start"),
+            Self::RssiGainStep => Some("rx_gain_step_cal"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EnsmMode {
+    Sleep,
+    Wait,
+    Alert,
+    Rx,
+    Tx,
+    Fdd,
+    Tdd,
+    PinCtrl,
+    PinCtrlFddIndep,
+}
+
+impl EnsmMode {
+    pub fn get_value(&self) -> &'static str {
+        match self {
+            Self::Sleep => "sleep",
+            Self::Wait => "wait",
+            Self::Alert => "alert",
+            Self::Rx => "rx",
+            Self::Tx => "tx",
+            Self::Fdd => "fdd",
+            Self::Tdd => "tdd",
+            Self::PinCtrl => "pinctrl",
+            Self::PinCtrlFddIndep => "pinctrl_fdd_indep",
+        }
+    }
+
+    pub fn from_value(value: &str) -> Result<EnsmMode, ()> {
+        match value.trim() {
+            "sleep" => Ok(Self::Sleep),
+            "wait" => Ok(Self::Wait),
+            "alert" => Ok(Self::Alert),
+            "rx" => Ok(Self::Rx),
+            "tx" => Ok(Self::Tx),
+            "fdd" => Ok(Self::Fdd),
+            "tdd" => Ok(Self::Tdd),
+            "pinctrl" => Ok(Self::PinCtrl),
+            "pinctrl_fdd_indep" => Ok(Self::PinCtrlFddIndep),
+            _ => Err(()),
+        }
+    }
+
+    // The ENSM only reaches the active states from alert, and only
+    // drops to sleep or wait from alert
+    pub fn can_transition_to(&self, target: EnsmMode) -> bool {
+        match (self, target) {
+            (current, target) if *current == target => true,
+            (_, Self::Alert) => true,
+            (Self::Alert, _) => true,
+            (Self::Sleep, Self::Wait) | (Self::Wait, Self::Sleep) => true,
+            (Self::Rx | Self::Tx, Self::Tdd | Self::Rx | Self::Tx) => true,
+            _ => false,
+        }
+    }
+
+    // Whether a read-back state is consistent with the requested mode. In TDD
+    // and pin control modes the state follows the TXNRX/ENABLE pins or SPI
+    pub fn matches_state(&self, state: EnsmMode) -> bool {
+        match self {
+            Self::Tdd => matches!(state, Self::Tdd | Self::Rx | Self::Tx | Self::Alert),
+            Self::PinCtrl | Self::PinCtrlFddIndep => true,
+            _ => *self == state,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Synthesizer {
+    Rx,
+    Tx,
+}
+
+impl Synthesizer {
+    pub fn get_value(&self) -> &'static str {
+        match self {
+            Self::Rx => "RX",
+            Self::Tx => "TX",
+        }
+    }
+
+    pub fn from_value(value: &str) -> Result<Synthesizer, ()> {
+        match value.trim() {
+            "RX" => Ok(Self::Rx),
+            "TX" => Ok(Self::Tx),
+            _ => Err(()),
+        }
+    }
+
+    pub fn get_index(&self) -> usize {
+        match self {
+            Self::Rx => 0,
+            Self::Tx => 1,
+        }
+    }
+
+    // LO output channel of the ad9361-phy driving this synthesizer
+    pub fn get_channel_name(&self) -> &'static str {
+        match self {
+            Self::Rx => "altvoltage0",
+            Self::Tx => "altvoltage1",
+        }
+    }
+}
+
+// Above this LO the wideband A ports are preferred over the B/C ports
+pub static RF_PORT_HIGH_BAND: u64 = 3_000_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "SCREAMING_SNAKE_CASE"))]
+pub enum RxPort {
+    ABalanced,
+    BBalanced,
+    CBalanced,
+    AN,
+    AP,
+    BN,
+    BP,
+    CN,
+    CP,
+    TxMonitor1,
+    TxMonitor2,
+    #[cfg_attr(feature = "serde", serde(rename = "TX_MONITOR1_2"))]
+    TxMonitor12,
+}
+
+impl RxPort {
+    pub fn get_value(&self) -> &'static str {
+        match self {
+            Self::ABalanced => "A_BALANCED",
+            Self::BBalanced => "B_BALANCED",
+            Self::CBalanced => "C_BALANCED",
+            Self::AN => "A_N",
+            Self::AP => "A_P",
+            Self::BN => "B_N",
+            Self::BP => "B_P",
+            Self::CN => "C_N",
+            Self::CP => "C_P",
+            Self::TxMonitor1 => "TX_MONITOR1",
+            Self::TxMonitor2 => "TX_MONITOR2",
+            Self::TxMonitor12 => "TX_MONITOR1_2",
+        }
+    }
+
+    pub fn from_value(value: &str) -> Result<RxPort, ()> {
+        match value.trim() {
+            "A_BALANCED" => Ok(Self::ABalanced),
+            "B_BALANCED" => Ok(Self::BBalanced),
+            "C_BALANCED" => Ok(Self::CBalanced),
+            "A_N" => Ok(Self::AN),
+            "A_P" => Ok(Self::AP),
+            "B_N" => Ok(Self::BN),
+            "B_P" => Ok(Self::BP),
+            "C_N" => Ok(Self::CN),
+            "C_P" => Ok(Self::CP),
+            "TX_MONITOR1" => Ok(Self::TxMonitor1),
+            "TX_MONITOR2" => Ok(Self::TxMonitor2),
+            "TX_MONITOR1_2" => Ok(Self::TxMonitor12),
+            _ => Err(()),
+        }
+    }
+
+    // Picks the first usable balanced port suited to the LO band, falling back to the
+    // wideband A port
+    pub fn for_frequency(frequency: u64, available: &[RxPort]) -> Option<RxPort> {
+        let preference = if frequency > RF_PORT_HIGH_BAND {
+            [Self::ABalanced, Self::BBalanced, Self::CBalanced]
+        } else {
+            [Self::BBalanced, Self::CBalanced, Self::ABalanced]
+        };
+        preference.into_iter().find(|f| available.contains(f))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TxPort {
+    A,
+    B,
+}
+
+impl TxPort {
+    pub fn get_value(&self) -> &'static str {
+        match self {
+            Self::A => "A",
+            Self::B => "B",
+        }
+    }
+
+    pub fn from_value(value: &str) -> Result<TxPort, ()> {
+        match value.trim() {
+            "A" => Ok(Self::A),
+            "B" => Ok(Self::B),
+            _ => Err(()),
+        }
+    }
+
+    pub fn for_frequency(frequency: u64, available: &[TxPort]) -> Option<TxPort> {
+        let preference = if frequency > RF_PORT_HIGH_BAND {
+            [Self::A, Self::B]
+        } else {
+            [Self::B, Self::A]
+        };
+        preference.into_iter().find(|f| available.contains(f))
+    }
+}
+
+// Clock chain as reported by rx_path_rates / tx_path_rates, e.g.
+// "BBPLL:983040000 ADC:245760000 R2:122880000 R1:61440000 RF:30720000 RXSAMP:30720000"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathRates {
+    pub bbpll: u64,
+    // ADC rate on RX, DAC rate on TX
+    pub converter: u64,
+    pub r2: u64,
+    pub r1: u64,
+    pub rf: u64,
+    pub sample: u64,
+}
+
+impl PathRates {
+    pub fn parse(value: &str, output: bool) -> Result<PathRates, ()> {
+        let names = if output {
+            ["BBPLL", "DAC", "T2", "T1", "TF", "TXSAMP"]
+        } else {
+            ["BBPLL", "ADC", "R2", "R1", "RF", "RXSAMP"]
+        };
+        let entries = value
+            .split_whitespace()
+            .map(|f| {
+                let (name, rate) = f.split_once(':').ok_or(())?;
+                Ok((name, rate.parse::<u64>().map_err(|_| ())?))
+            })
+            .collect::<Result<Vec<(&str, u64)>, ()>>()?;
+        let mut rates = [0u64; 6];
+        for (rate, name) in rates.iter_mut().zip(names) {
+            *rate = entries
+                .iter()
+                .find(|(f, _)| *f == name)
+                .map(|(_, f)| *f)
+                .ok_or(())?;
+        }
+        if rates.contains(&0) {
+            return Err(());
+        }
+        Ok(PathRates {
+            bbpll: rates[0],
+            converter: rates[1],
+            r2: rates[2],
+            r1: rates[3],
+            rf: rates[4],
+            sample: rates[5],
+        })
+    }
+
+    // Rates in [BBPLL, ADC/DAC, R2/T2, R1/T1, RF/TF, SAMP] order, as in the RRX/RTX .ftr lines
+    pub fn from_values(values: &[u64]) -> Result<PathRates, ()> {
+        match values {
+            [bbpll, converter, r2, r1, rf, sample] => Ok(PathRates {
+                bbpll: *bbpll,
+                converter: *converter,
+                r2: *r2,
+                r1: *r1,
+                rf: *rf,
+                sample: *sample,
+            }),
+            _ => Err(()),
+        }
+    }
+
+    pub fn get_values(&self) -> [u64; 6] {
+        [
+            self.bbpll,
+            self.converter,
+            self.r2,
+            self.r1,
+            self.rf,
+            self.sample,
+        ]
+    }
+
+    // Total decimation (RX) or interpolation (TX) between the converter and the samples
+    pub fn get_ratio(&self) -> u64 {
+        self.converter / self.sample
+    }
+
+    pub fn get_fir_ratio(&self) -> u64 {
+        self.rf / self.sample
+    }
+}
+
+// Range in the "[min step max]" form used by the *_available attributes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AttrRange {
+    pub min: f64,
+    pub step: f64,
+    pub max: f64,
+}
+
+impl AttrRange {
+    pub fn parse(value: &str) -> Result<AttrRange, ()> {
+        let values = value
+            .trim()
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .split_whitespace()
+            .map(|f| f.parse::<f64>().map_err(|_| ()))
+            .collect::<Result<Vec<f64>, ()>>()?;
+        match values.as_slice() {
+            [min, step, max] => Ok(AttrRange {
+                min: *min,
+                step: *step,
+                max: *max,
+            }),
+            _ => Err(()),
+        }
+    }
+
+    // Values must also sit on the step grid, within a tolerance for the decimal rounding
+    pub fn contains(&self, value: f64) -> bool {
+        if value < self.min || value > self.max {
+            return false;
+        }
+        if self.step <= 0.0 {
+            return true;
+        }
+        let steps = (value - self.min) / self.step;
+        (steps - steps.round()).abs() < 1e-6
+    }
+
+    pub fn check(&self, value: f64) -> Result<(), error::AdiError> {
+        if self.contains(value) {
+            Ok(())
+        } else {
+            Err(error::AdiError::RangeError {
+                value: value.to_string(),
+                available: self.to_string(),
+            })
+        }
+    }
+}
+
+impl std::fmt::Display for AttrRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{} {} {}]", self.min, self.step, self.max)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChipModel {
+    AD9361,
+    AD9363,
+    AD9364,
+}
+
+impl ChipModel {
+    pub fn get_value(&self) -> &'static str {
+        match self {
+            Self::AD9361 => "ad9361",
+            Self::AD9363 => "ad9363",
+            Self::AD9364 => "ad9364",
+        }
+    }
+
+    // Accepts driver models like "ad9363a" as well as hw_model strings
+    // such as "Analog Devices PlutoSDR Rev.C (Z7010-AD9363A)"
+    pub fn from_value(value: &str) -> Result<ChipModel, ()> {
+        let value = value.to_lowercase();
+        [Self::AD9361, Self::AD9363, Self::AD9364]
+            .into_iter()
+            .find(|f| value.contains(f.get_value()))
+            .ok_or(())
+    }
+
+    // Datasheet limits, used when the driver does not report *_available ranges
+    pub fn get_lo_range(&self) -> AttrRange {
+        match self {
+            Self::AD9363 => AttrRange {
+                min: 325_000_000.0,
+                step: 1.0,
+                max: 3_800_000_000.0,
+            },
+            Self::AD9361 | Self::AD9364 => AttrRange {
+                min: 70_000_000.0,
+                step: 1.0,
+                max: 6_000_000_000.0,
+            },
+        }
+    }
+
+    pub fn get_rf_bandwidth_range(&self) -> AttrRange {
+        match self {
+            Self::AD9363 => AttrRange {
+                min: 200_000.0,
+                step: 1.0,
+                max: 20_000_000.0,
+            },
+            Self::AD9361 | Self::AD9364 => AttrRange {
+                min: 200_000.0,
+                step: 1.0,
+                max: 56_000_000.0,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RadioRanges {
+    pub chip: ChipModel,
+    // False when no model string named the chip and AD9363 was assumed
+    pub chip_detected: bool,
+    pub rx_lo: AttrRange,
+    pub tx_lo: AttrRange,
+    pub rx_rf_bandwidth: AttrRange,
+    pub tx_rf_bandwidth: AttrRange,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tx_sample_components() {
+        assert_eq!(i16::COMPONENTS, 1);
+        assert_eq!(1234i16.components()[0], 1234);
+        let complex = num::complex::Complex::<i16>::new(-5, 7);
+        assert_eq!(num::complex::Complex::<i16>::COMPONENTS, 2);
+        assert_eq!(complex.components(), [-5, 7]);
+    }
+
+    #[test]
+    fn pluto_complex_scaling() {
+        assert_eq!(PlutoComplex::COMPONENTS, 2);
+        assert_eq!(PlutoComplex::new(0.5, -0.25).components(), [8192, -4096]);
+        // Whole values match the former (value as i128) << 14 conversion
+        for value in [-1.0f32, 0.0, 1.0] {
+            let sample = PlutoComplex::new(value, value).components();
+            assert_eq!(sample[0] as i128, (value as i128) << 14);
+        }
+    }
+
+    #[test]
+    fn calibration_polled_bits() {
+        // run_calibration waits for these calibration_ctrl (0x016) bits to clear
+        let register = crate::registers::find_register("calibration_ctrl").unwrap();
+        let mask = |mode: CalibrationMode| {
+            mode.get_calibration_field()
+                .map(|f| register.find_field(f).unwrap().get_mask())
+        };
+        assert_eq!(mask(CalibrationMode::Auto), None);
+        assert_eq!(mask(CalibrationMode::Manual), None);
+        assert_eq!(mask(CalibrationMode::ManualTxQuad), Some(1 << 4));
+        assert_eq!(mask(CalibrationMode::TxQuad), Some(1 << 4));
+        assert_eq!(mask(CalibrationMode::RfDcOffs), Some(1 << 1));
+        assert_eq!(mask(CalibrationMode::RssiGainStep), Some(1 << 3));
+    }
+
+    static ENSM_MODES: [EnsmMode; 9] = [
+        EnsmMode::Sleep,
+        EnsmMode::Wait,
+        EnsmMode::Alert,
+        EnsmMode::Rx,
+        EnsmMode::Tx,
+        EnsmMode::Fdd,
+        EnsmMode::Tdd,
+        EnsmMode::PinCtrl,
+        EnsmMode::PinCtrlFddIndep,
+    ];
+
+    #[test]
+    fn ensm_mode_values() {
+        for mode in ENSM_MODES {
+            assert_eq!(EnsmMode::from_value(mode.get_value()), Ok(mode));
+        }
+        assert_eq!(EnsmMode::from_value("fdd\n"), Ok(EnsmMode::Fdd));
+        assert!(EnsmMode::from_value("idle").is_err());
+    }
+
+    #[test]
+    fn ensm_transitions() {
+        // Direct transitions besides staying put and going through alert
+        let direct = [
+            (EnsmMode::Sleep, EnsmMode::Wait),
+            (EnsmMode::Wait, EnsmMode::Sleep),
+            (EnsmMode::Rx, EnsmMode::Tx),
+            (EnsmMode::Rx, EnsmMode::Tdd),
+            (EnsmMode::Tx, EnsmMode::Rx),
+            (EnsmMode::Tx, EnsmMode::Tdd),
+        ];
+        for current in ENSM_MODES {
+            for target in ENSM_MODES {
+                let expected = current == target
+                    || current == EnsmMode::Alert
+                    || target == EnsmMode::Alert
+                    || direct.contains(&(current, target));
+                assert_eq!(
+                    current.can_transition_to(target),
+                    expected,
+                    "{:?} -> {:?}",
+                    current,
+                    target
+                );
+            }
+        }
+        assert!(!EnsmMode::Sleep.can_transition_to(EnsmMode::Rx));
+        assert!(!EnsmMode::Fdd.can_transition_to(EnsmMode::Sleep));
+        assert!(!EnsmMode::Tdd.can_transition_to(EnsmMode::Fdd));
+    }
+
+    #[test]
+    fn path_rates_parse() {
+        let rx = PathRates::parse(
+            "BBPLL:983040000 ADC:245760000 R2:122880000 R1:61440000 RF:30720000 RXSAMP:30720000\n",
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            rx.get_values(),
+            [
+                983_040_000,
+                245_760_000,
+                122_880_000,
+                61_440_000,
+                30_720_000,
+                30_720_000
+            ]
+        );
+        assert_eq!(rx.get_ratio(), 8);
+        assert_eq!(rx.get_fir_ratio(), 1);
+
+        let tx = PathRates::parse(
+            "BBPLL:983040000 DAC:122880000 T2:61440000 T1:30720000 TF:15360000 TXSAMP:3840000",
+            true,
+        )
+        .unwrap();
+        assert_eq!(tx.converter, 122_880_000);
+        assert_eq!(tx.sample, 3_840_000);
+        assert_eq!(tx.get_ratio(), 32);
+        assert_eq!(tx.get_fir_ratio(), 4);
+        assert_eq!(PathRates::from_values(&tx.get_values()), Ok(tx));
+    }
+
+    #[test]
+    fn path_rates_parse_invalid() {
+        let rx =
+            "BBPLL:983040000 ADC:245760000 R2:122880000 R1:61440000 RF:30720000 RXSAMP:30720000";
+        // TX names are expected for the TX path
+        assert!(PathRates::parse(rx, true).is_err());
+        assert!(PathRates::parse("BBPLL:983040000 ADC:245760000", false).is_err());
+        assert!(
+            PathRates::parse(
+                "BBPLL:983040000 ADC:0 R2:122880000 R1:61440000 RF:30720000 RXSAMP:30720000",
+                false
+            )
+            .is_err()
+        );
+        assert!(
+            PathRates::parse(
+                "BBPLL:983040000 ADC:245.76M R2:122880000 R1:61440000 RF:30720000 RXSAMP:30720000",
+                false
+            )
+            .is_err()
+        );
+        assert!(PathRates::from_values(&[1, 2, 3]).is_err());
+    }
+}
@@ -1,5 +1,71 @@
-#[derive(Debug)]
+use crate::attribute;
+
+#[derive(Debug, PartialEq)]
 pub enum AdiError {
     NullPointerError,
     NegativeValueError(i32),
+    // Requested value lies outside what the device reports as available
+    RangeError {
+        value: String,
+        available: String,
+    },
+    IioError,
+    TimeoutError,
+    // Settings whose read-back differs from what was written
+    VerificationError(Vec<String>),
+    // The LO is powered down or driven externally
+    LoNotTunableError,
+    // No RF port both wired on the board and available from the driver suits the LO
+    NoRfPortError(u64),
+    // Transaction write without the explicit rollback value its attribute requires
+    MissingRollbackError(String),
+    // Attributes left modified after a transaction failed and could not be undone
+    RollbackError(Vec<String>),
+    // Value of another type than the attribute holds
+    TypeMismatchError {
+        expected: attribute::AttrValueType,
+        found: attribute::AttrValueType,
+    },
 }
+
+impl From<()> for AdiError {
+    fn from(_: ()) -> Self {
+        AdiError::IioError
+    }
+}
+
+impl std::fmt::Display for AdiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NullPointerError => write!(f, "null pointer"),
+            Self::NegativeValueError(value) => write!(f, "negative value {}", value),
+            Self::RangeError { value, available } => {
+                write!(f, "{} is out of range, available: {}", value, available)
+            }
+            Self::IioError => write!(f, "IIO operation failed"),
+            Self::TimeoutError => write!(f, "operation timed out"),
+            Self::VerificationError(settings) => {
+                write!(f, "read-back mismatch: {}", settings.join(", "))
+            }
+            Self::LoNotTunableError => write!(f, "LO is powered down or external"),
+            Self::NoRfPortError(frequency) => {
+                write!(f, "no usable RF port for LO {} Hz", frequency)
+            }
+            Self::MissingRollbackError(attr) => {
+                write!(
+                    f,
+                    "{} cannot be read back, a rollback value is required",
+                    attr
+                )
+            }
+            Self::RollbackError(attrs) => {
+                write!(f, "rollback failed, left modified: {}", attrs.join(", "))
+            }
+            Self::TypeMismatchError { expected, found } => {
+                write!(f, "expected a {:?} value, got {:?}", expected, found)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AdiError {}
@@ -0,0 +1,251 @@
+use crate::datatypes;
+
+// Each AD9361 synthesizer holds 8 fastlock profiles of 16 configuration words
+pub static FASTLOCK_PROFILES: u8 = 8;
+pub static FASTLOCK_CONFIG_WORDS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FastlockProfile {
+    synthesizer: datatypes::Synthesizer,
+    index: u8,
+    frequency: u64,
+    data: Vec<u8>,
+}
+
+impl FastlockProfile {
+    pub fn new(
+        synthesizer: datatypes::Synthesizer,
+        index: u8,
+        frequency: u64,
+        data: Vec<u8>,
+    ) -> Result<FastlockProfile, ()> {
+        if index >= FASTLOCK_PROFILES || data.len() != FASTLOCK_CONFIG_WORDS {
+            return Err(());
+        }
+        Ok(FastlockProfile {
+            synthesizer,
+            index,
+            frequency,
+            data,
+        })
+    }
+
+    // Parses the "index v0,v1,...,v15" form read back from fastlock_save
+    pub fn parse_save(
+        synthesizer: datatypes::Synthesizer,
+        frequency: u64,
+        value: &str,
+    ) -> Result<FastlockProfile, ()> {
+        let (index, data) = value.trim().split_once(' ').ok_or(())?;
+        let index = index.parse::<u8>().map_err(|_| ())?;
+        let data = data
+            .trim()
+            .split(',')
+            .map(|f| f.trim().parse::<u8>().map_err(|_| ()))
+            .collect::<Result<Vec<u8>, ()>>()?;
+        FastlockProfile::new(synthesizer, index, frequency, data)
+    }
+
+    // Value to write to fastlock_load to program this profile back into the synthesizer
+    pub fn get_load_value(&self) -> String {
+        format!("{} {}", self.index, self.get_data_string())
+    }
+
+    fn get_data_string(&self) -> String {
+        self.data
+            .iter()
+            .map(|f| f.to_string())
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+
+    pub fn get_synthesizer(&self) -> datatypes::Synthesizer {
+        self.synthesizer
+    }
+
+    pub fn get_index(&self) -> u8 {
+        self.index
+    }
+
+    pub fn get_frequency(&self) -> u64 {
+        self.frequency
+    }
+
+    pub fn get_data(&self) -> &[u8] {
+        self.data.as_slice()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FastlockStore {
+    profiles: Vec<FastlockProfile>,
+}
+
+impl FastlockStore {
+    pub fn new() -> FastlockStore {
+        FastlockStore { profiles: vec![] }
+    }
+
+    pub fn parse(data: &str) -> Result<FastlockStore, ()> {
+        let mut store = FastlockStore::new();
+        for line in data.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let tokens = line.split_whitespace().collect::<Vec<&str>>();
+            if tokens.len() != 4 {
+                return Err(());
+            }
+            let synthesizer = datatypes::Synthesizer::from_value(tokens[0])?;
+            let frequency = tokens[2].parse::<u64>().map_err(|_| ())?;
+            let value = format!("{} {}", tokens[1], tokens[3]);
+            store.insert(FastlockProfile::parse_save(
+                synthesizer,
+                frequency,
+                value.as_str(),
+            )?);
+        }
+        Ok(store)
+    }
+
+    pub fn load(filename: &str) -> Result<FastlockStore, ()> {
+        let data = std::fs::read_to_string(filename).map_err(|_| ())?;
+        FastlockStore::parse(data.as_str())
+    }
+
+    pub fn save(&self, filename: &str) -> Result<(), ()> {
+        std::fs::write(filename, self.to_string()).map_err(|_| ())
+    }
+
+    // Replaces any profile already stored in the same synthesizer slot
+    pub fn insert(&mut self, profile: FastlockProfile) {
+        self.profiles
+            .retain(|f| f.synthesizer != profile.synthesizer || f.index != profile.index);
+        self.profiles.push(profile);
+        self.profiles
+            .sort_by_key(|f| (f.synthesizer.get_value(), f.index));
+    }
+
+    pub fn get_profiles(&self) -> &[FastlockProfile] {
+        self.profiles.as_slice()
+    }
+
+    pub fn get_profile(
+        &self,
+        synthesizer: datatypes::Synthesizer,
+        index: u8,
+    ) -> Option<&FastlockProfile> {
+        self.profiles
+            .iter()
+            .find(|f| f.synthesizer == synthesizer && f.index == index)
+    }
+
+    pub fn find_frequency(
+        &self,
+        synthesizer: datatypes::Synthesizer,
+        frequency: u64,
+    ) -> Option<&FastlockProfile> {
+        self.profiles
+            .iter()
+            .find(|f| f.synthesizer == synthesizer && f.frequency == frequency)
+    }
+}
+
+impl std::fmt::Display for FastlockStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# synthesizer index frequency data")?;
+        for profile in self.profiles.iter() {
+            writeln!(
+                f,
+                "{} {} {} {}",
+                profile.synthesizer.get_value(),
+                profile.index,
+                profile.frequency,
+                profile.get_data_string()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(synthesizer: datatypes::Synthesizer, index: u8, frequency: u64) -> FastlockProfile {
+        let data = (0..FASTLOCK_CONFIG_WORDS as u8)
+            .map(|f| f * 3 + index)
+            .collect();
+        FastlockProfile::new(synthesizer, index, frequency, data).unwrap()
+    }
+
+    #[test]
+    fn parse_save_value() {
+        let profile = FastlockProfile::parse_save(
+            datatypes::Synthesizer::Rx,
+            2_400_000_000,
+            "3 1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,255\n",
+        )
+        .unwrap();
+        assert_eq!(profile.get_index(), 3);
+        assert_eq!(profile.get_data()[15], 255);
+        assert_eq!(
+            profile.get_load_value(),
+            "3 1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,255"
+        );
+        assert!(FastlockProfile::parse_save(datatypes::Synthesizer::Rx, 0, "8 0").is_err());
+        assert!(FastlockProfile::parse_save(datatypes::Synthesizer::Rx, 0, "0 1,2,3").is_err());
+        assert!(
+            FastlockProfile::parse_save(
+                datatypes::Synthesizer::Rx,
+                0,
+                "0 1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,256"
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn store_round_trip() {
+        let mut store = FastlockStore::new();
+        store.insert(profile(datatypes::Synthesizer::Tx, 1, 915_000_000));
+        store.insert(profile(datatypes::Synthesizer::Rx, 7, 2_450_000_000));
+        store.insert(profile(datatypes::Synthesizer::Rx, 0, 433_000_000));
+        // Same slot, replaces the previous profile
+        store.insert(profile(datatypes::Synthesizer::Rx, 0, 868_000_000));
+        assert_eq!(store.get_profiles().len(), 3);
+
+        let parsed = FastlockStore::parse(store.to_string().as_str()).unwrap();
+        assert_eq!(parsed, store);
+        assert_eq!(
+            parsed
+                .find_frequency(datatypes::Synthesizer::Rx, 868_000_000)
+                .map(|f| f.get_index()),
+            Some(0)
+        );
+        assert!(
+            parsed
+                .find_frequency(datatypes::Synthesizer::Rx, 433_000_000)
+                .is_none()
+        );
+
+        let path = std::env::temp_dir().join(format!("adi-fastlock-{}", std::process::id()));
+        let filename = path.to_string_lossy().into_owned();
+        store.save(filename.as_str()).unwrap();
+        let loaded = FastlockStore::load(filename.as_str());
+        std::fs::remove_file(filename.as_str()).unwrap();
+        assert_eq!(loaded, Ok(store));
+    }
+
+    #[test]
+    fn store_parse_invalid() {
+        assert_eq!(
+            FastlockStore::parse("# only a comment\n\n"),
+            Ok(FastlockStore::new())
+        );
+        assert!(FastlockStore::parse("RX 0 2400000000").is_err());
+        assert!(FastlockStore::parse("XX 0 2400000000 0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0").is_err());
+        assert!(FastlockStore::parse("RX 0 2.4e9 0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0").is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use crate::datatypes;
+
 pub static FIR_1: [i32; 128] = [
     -15, -27, -23, -6, 17, 33, 31, 9, -23, -47, -45, -13, 34, 69, 67, 21, -49, -102, -99, -32, 69,
     146, 143, 48, -96, -204, -200, -69, 129, 278, 275, 97, -170, -372, -371, -135, 222, 494, 497,
@@ -32,3 +34,534 @@ pub static FIR_4: [i32; 64] = [
     -0, 1954, 0, -1466, -0, 1117, 0, -853, -0, 648, 0, -488, -0, 361, 0, -262, -0, 185, 0, -127,
     -0, 83, 0, -58, 0,
 ];
+
+static RX_GAINS: [i32; 4] = [-12, -6, 0, 6];
+static TX_GAINS: [i32; 2] = [-6, 0];
+static DEC_INT_FACTORS: [u32; 3] = [1, 2, 4];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FirFilter {
+    pub rx_channels: u32,
+    pub rx_gain: i32,
+    pub decimation: u32,
+    pub tx_channels: u32,
+    pub tx_gain: i32,
+    pub interpolation: u32,
+    pub rx_path_rates: Option<datatypes::PathRates>,
+    pub tx_path_rates: Option<datatypes::PathRates>,
+    pub rx_bandwidth: Option<u32>,
+    pub tx_bandwidth: Option<u32>,
+    pub rx_coefficients: Vec<i16>,
+    pub tx_coefficients: Vec<i16>,
+}
+
+impl FirFilter {
+    pub fn new(
+        decimation: u32,
+        interpolation: u32,
+        rx_gain: i32,
+        tx_gain: i32,
+        coefficients: Vec<i16>,
+    ) -> FirFilter {
+        FirFilter {
+            rx_channels: 3,
+            rx_gain,
+            decimation,
+            tx_channels: 3,
+            tx_gain,
+            interpolation,
+            rx_path_rates: None,
+            tx_path_rates: None,
+            rx_bandwidth: None,
+            tx_bandwidth: None,
+            rx_coefficients: coefficients.clone(),
+            tx_coefficients: coefficients,
+        }
+    }
+
+    pub fn parse(data: &str) -> Result<FirFilter, ()> {
+        let mut rx = None;
+        let mut tx = None;
+        let mut filter = FirFilter::new(1, 1, 0, 0, vec![]);
+
+        for line in data.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let tokens = line.split_whitespace().collect::<Vec<&str>>();
+            match tokens[0] {
+                "RX" | "TX" => {
+                    if tokens.len() != 6 || tokens[2] != "GAIN" {
+                        return Err(());
+                    }
+                    let channels = tokens[1].parse::<u32>().map_err(|_| ())?;
+                    let gain = tokens[3].parse::<i32>().map_err(|_| ())?;
+                    let factor = tokens[5].parse::<u32>().map_err(|_| ())?;
+                    match (tokens[0], tokens[4]) {
+                        ("RX", "DEC") => rx = Some((channels, gain, factor)),
+                        ("TX", "INT") => tx = Some((channels, gain, factor)),
+                        _ => return Err(()),
+                    }
+                }
+                "RRX" | "RTX" => {
+                    let rates = tokens[1..]
+                        .iter()
+                        .map(|f| f.parse::<u64>().map_err(|_| ()))
+                        .collect::<Result<Vec<u64>, ()>>()?;
+                    let rates = datatypes::PathRates::from_values(rates.as_slice())?;
+                    if tokens[0] == "RRX" {
+                        filter.rx_path_rates = Some(rates);
+                    } else {
+                        filter.tx_path_rates = Some(rates);
+                    }
+                }
+                "BWRX" | "BWTX" => {
+                    let bandwidth = tokens.get(1).ok_or(())?.parse::<u32>().map_err(|_| ())?;
+                    if tokens[0] == "BWRX" {
+                        filter.rx_bandwidth = Some(bandwidth);
+                    } else {
+                        filter.tx_bandwidth = Some(bandwidth);
+                    }
+                }
+                _ => {
+                    // Coefficient lines hold a TX,RX pair, a single value applies to both
+                    let values = line
+                        .split([',', ' ', '\t'])
+                        .filter(|f| !f.is_empty())
+                        .map(|f| f.parse::<i16>().map_err(|_| ()))
+                        .collect::<Result<Vec<i16>, ()>>()?;
+                    match values.as_slice() {
+                        [both] => {
+                            filter.tx_coefficients.push(*both);
+                            filter.rx_coefficients.push(*both);
+                        }
+                        [tx_coefficient, rx_coefficient] => {
+                            filter.tx_coefficients.push(*tx_coefficient);
+                            filter.rx_coefficients.push(*rx_coefficient);
+                        }
+                        _ => return Err(()),
+                    }
+                }
+            }
+        }
+
+        let (rx_channels, rx_gain, decimation) = match (rx, tx) {
+            (Some(rx), _) => rx,
+            (None, Some((channels, _, factor))) => (channels, 0, factor),
+            (None, None) => return Err(()),
+        };
+        let (tx_channels, tx_gain, interpolation) = tx.unwrap_or((rx_channels, 0, decimation));
+        filter.rx_channels = rx_channels;
+        filter.rx_gain = rx_gain;
+        filter.decimation = decimation;
+        filter.tx_channels = tx_channels;
+        filter.tx_gain = tx_gain;
+        filter.interpolation = interpolation;
+        filter.validate()?;
+        Ok(filter)
+    }
+
+    pub fn load(filename: &str) -> Result<FirFilter, ()> {
+        let data = std::fs::read_to_string(filename).map_err(|_| ())?;
+        FirFilter::parse(data.as_str())
+    }
+
+    pub fn save(&self, filename: &str) -> Result<(), ()> {
+        std::fs::write(filename, self.to_string()).map_err(|_| ())
+    }
+
+    // The FIR computes 16 taps per converter clock between two samples, unknown
+    // rates leave only the fixed limits
+    fn get_clock_taps(rates: Option<&datatypes::PathRates>) -> usize {
+        match rates {
+            Some(rates) => rates
+                .converter
+                .checked_div(rates.sample)
+                .map(|f| 16 * f as usize)
+                .unwrap_or(0),
+            None => usize::MAX,
+        }
+    }
+
+    pub fn get_max_rx_taps(&self) -> usize {
+        FirFilter::get_clock_taps(self.rx_path_rates.as_ref()).min(128)
+    }
+
+    // Without interpolation the TX FIR only has time for half the taps
+    pub fn get_max_tx_taps(&self) -> usize {
+        let max_taps = if self.interpolation == 1 { 64 } else { 128 };
+        FirFilter::get_clock_taps(self.tx_path_rates.as_ref()).min(max_taps)
+    }
+
+    pub fn validate(&self) -> Result<(), ()> {
+        if !(1..=3).contains(&self.rx_channels) || !(1..=3).contains(&self.tx_channels) {
+            return Err(());
+        }
+        if !RX_GAINS.contains(&self.rx_gain) || !TX_GAINS.contains(&self.tx_gain) {
+            return Err(());
+        }
+        if !DEC_INT_FACTORS.contains(&self.decimation)
+            || !DEC_INT_FACTORS.contains(&self.interpolation)
+        {
+            return Err(());
+        }
+
+        // Both chains are loaded from the same table, taps come by blocks of 16
+        let taps = self.rx_coefficients.len();
+        if taps != self.tx_coefficients.len() || taps == 0 || !taps.is_multiple_of(16) {
+            return Err(());
+        }
+        if taps > self.get_max_rx_taps() || taps > self.get_max_tx_taps() {
+            return Err(());
+        }
+
+        Ok(())
+    }
+
+    // The driver only reports "FIR Rx: <taps>,<dec> Tx: <taps>,<int>" for the loaded filter
+    pub fn matches_status(&self, status: &str) -> Result<bool, ()> {
+        let mut values = vec![];
+        for part in status.split_whitespace() {
+            if part.ends_with(':') || part == "FIR" {
+                continue;
+            }
+            for value in part.split(',') {
+                values.push(value.parse::<u32>().map_err(|_| ())?);
+            }
+        }
+        match values.as_slice() {
+            [rx_taps, decimation, tx_taps, interpolation] => Ok(*rx_taps as usize
+                == self.rx_coefficients.len()
+                && *decimation == self.decimation
+                && *tx_taps as usize == self.tx_coefficients.len()
+                && *interpolation == self.interpolation),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for FirFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "TX {} GAIN {} INT {}",
+            self.tx_channels, self.tx_gain, self.interpolation
+        )?;
+        writeln!(
+            f,
+            "RX {} GAIN {} DEC {}",
+            self.rx_channels, self.rx_gain, self.decimation
+        )?;
+        for (name, rates) in [("RTX", &self.tx_path_rates), ("RRX", &self.rx_path_rates)] {
+            if let Some(rates) = rates {
+                let rates_string = rates
+                    .get_values()
+                    .iter()
+                    .map(|f| f.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                writeln!(f, "{} {}", name, rates_string)?;
+            }
+        }
+        for (name, bandwidth) in [("BWTX", self.tx_bandwidth), ("BWRX", self.rx_bandwidth)] {
+            if let Some(bandwidth) = bandwidth {
+                writeln!(f, "{} {}", name, bandwidth)?;
+            }
+        }
+        for (tx_coefficient, rx_coefficient) in
+            std::iter::zip(self.tx_coefficients.iter(), self.rx_coefficients.iter())
+        {
+            writeln!(f, "{},{}", tx_coefficient, rx_coefficient)?;
+        }
+        writeln!(f)
+    }
+}
+
+static MIN_BBPLL_RATE: u64 = 715_000_000;
+static MAX_BBPLL_RATE: u64 = 1_430_000_000;
+static MIN_ADC_RATE: u64 = 25_000_000;
+static MAX_ADC_RATE: u64 = 640_000_000;
+static MAX_R2_RATE: u64 = 320_000_000;
+static MAX_R1_RATE: u64 = 160_000_000;
+static MAX_RF_RATE: u64 = 122_880_000;
+// Half band combinations supported by the driver, (total, HB3, HB2, HB1)
+static HB_DIVIDERS: [(u64, u64, u64, u64); 7] = [
+    (12, 3, 2, 2),
+    (8, 2, 2, 2),
+    (6, 3, 1, 2),
+    (4, 2, 2, 1),
+    (3, 3, 1, 1),
+    (2, 2, 1, 1),
+    (1, 1, 1, 1),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FirDesign {
+    pub sample_rate: u32,
+    pub rf_bandwidth: u32,
+    pub passband: u32,
+    pub stopband: u32,
+    pub attenuation_db: f64,
+}
+
+// Zeroth order modified Bessel function of the first kind, used by the Kaiser window
+fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let mut k = 1.0;
+    while term > sum * 1e-12 {
+        term *= (x / (2.0 * k)) * (x / (2.0 * k));
+        sum += term;
+        k += 1.0;
+    }
+    sum
+}
+
+impl FirDesign {
+    pub fn new(sample_rate: u32, rf_bandwidth: u32) -> FirDesign {
+        FirDesign {
+            sample_rate,
+            rf_bandwidth,
+            passband: rf_bandwidth / 2,
+            stopband: sample_rate / 2,
+            attenuation_db: 60.0,
+        }
+    }
+
+    // Returns the RX and TX clock chains and the FIR decimation, preferring the chain which
+    // decimates the most. Like the driver, the DAC runs at half the ADC rate when the RX half
+    // band filters decimate by more than 3, the TX half band filters then interpolate by half
+    // as much to reach the same sample rate.
+    pub fn get_clock_chain(&self) -> Result<(datatypes::PathRates, datatypes::PathRates, u32), ()> {
+        let sample_rate = self.sample_rate as u64;
+        let mut candidates = vec![];
+        for fir in [1u64, 2, 4] {
+            for dividers in HB_DIVIDERS.iter() {
+                candidates.push((fir, *dividers));
+            }
+        }
+        candidates.sort_by_key(|(fir, (total, _, _, _))| std::cmp::Reverse(fir * total));
+
+        for (fir, (total, hb3, hb2, hb1)) in candidates {
+            let rf = sample_rate * fir;
+            let r1 = rf * hb1;
+            let r2 = r1 * hb2;
+            let adc = r2 * hb3;
+            if adc != rf * total
+                || !(MIN_ADC_RATE..=MAX_ADC_RATE).contains(&adc)
+                || r2 > MAX_R2_RATE
+                || r1 > MAX_R1_RATE
+                || rf > MAX_RF_RATE
+            {
+                continue;
+            }
+            let (dac, tx_total) = if total > 3 {
+                (adc / 2, total / 2)
+            } else {
+                (adc, total)
+            };
+            let Some((_, t3, t2, t1)) = HB_DIVIDERS.iter().find(|f| f.0 == tx_total) else {
+                continue;
+            };
+            let t1_rate = rf * t1;
+            let t2_rate = t1_rate * t2;
+            if t2_rate * t3 != dac {
+                continue;
+            }
+            let bbpll = (1..=6)
+                .map(|f| adc << f)
+                .find(|f| (MIN_BBPLL_RATE..=MAX_BBPLL_RATE).contains(f));
+            if let Some(bbpll) = bbpll {
+                let rx_path_rates =
+                    datatypes::PathRates::from_values(&[bbpll, adc, r2, r1, rf, sample_rate])?;
+                let tx_path_rates = datatypes::PathRates::from_values(&[
+                    bbpll,
+                    dac,
+                    t2_rate,
+                    t1_rate,
+                    rf,
+                    sample_rate,
+                ])?;
+                return Ok((rx_path_rates, tx_path_rates, fir as u32));
+            }
+        }
+
+        Err(())
+    }
+
+    // Worst stopband response relative to DC, in dB, evaluated on a grid up to the FIR
+    // Nyquist rate
+    pub fn get_stopband_attenuation(&self, coefficients: &[i16], fir_rate: f64) -> f64 {
+        let response = |frequency: f64| {
+            let omega = 2.0 * std::f64::consts::PI * frequency / fir_rate;
+            let (re, im) = coefficients
+                .iter()
+                .enumerate()
+                .fold((0.0, 0.0), |(re, im), (n, c)| {
+                    let phase = omega * n as f64;
+                    (re + *c as f64 * phase.cos(), im - *c as f64 * phase.sin())
+                });
+            (re * re + im * im).sqrt()
+        };
+        let dc = response(0.0);
+        let points = 512;
+        let start = self.stopband as f64;
+        let end = fir_rate / 2.0;
+        let worst = (0..=points)
+            .map(|f| response(start + (end - start) * f as f64 / points as f64))
+            .fold(0.0f64, |acc, f| acc.max(f));
+        20.0 * (dc / worst).log10()
+    }
+
+    pub fn design(&self) -> Result<FirFilter, ()> {
+        if self.sample_rate == 0 || self.passband >= self.stopband {
+            return Err(());
+        }
+        let (rx_path_rates, tx_path_rates, factor) = self.get_clock_chain()?;
+        let fir_rate = rx_path_rates.rf as f64;
+        if self.stopband as f64 > fir_rate / 2.0 {
+            return Err(());
+        }
+
+        let mut filter = FirFilter::new(factor, factor, -6, 0, vec![]);
+        filter.rx_path_rates = Some(rx_path_rates);
+        filter.tx_path_rates = Some(tx_path_rates);
+        let taps = filter.get_max_rx_taps().min(filter.get_max_tx_taps());
+        if taps < 16 {
+            return Err(());
+        }
+
+        let attenuation = self.attenuation_db;
+        let beta = if attenuation > 50.0 {
+            0.1102 * (attenuation - 8.7)
+        } else if attenuation >= 21.0 {
+            0.5842 * (attenuation - 21.0).powf(0.4) + 0.07886 * (attenuation - 21.0)
+        } else {
+            0.0
+        };
+        let cutoff = (self.passband as f64 + self.stopband as f64) / 2.0 / fir_rate;
+        let center = (taps - 1) as f64 / 2.0;
+        let coefficients = (0..taps)
+            .map(|n| {
+                let x = n as f64 - center;
+                let sinc = if x == 0.0 {
+                    2.0 * cutoff
+                } else {
+                    (2.0 * std::f64::consts::PI * cutoff * x).sin() / (std::f64::consts::PI * x)
+                };
+                let ratio = x / center;
+                let window =
+                    bessel_i0(beta * (1.0 - ratio * ratio).max(0.0).sqrt()) / bessel_i0(beta);
+                sinc * window
+            })
+            .collect::<Vec<f64>>();
+
+        // Like the built-in tables the taps sum to 2^16 with a -6 dB RX gain, filters too
+        // wide for that range are halved and the RX gain raised, TX is then 6 dB lower
+        let sum = coefficients.iter().sum::<f64>();
+        let mut scale = 65536.0 / sum;
+        let peak = coefficients.iter().fold(0.0f64, |acc, f| acc.max(f.abs())) * scale;
+        if peak > 32768.0 {
+            scale /= 2.0;
+            filter.rx_gain = 0;
+        }
+        let quantized = coefficients
+            .iter()
+            .map(|f| (f * scale).round().clamp(i16::MIN as f64, i16::MAX as f64) as i16)
+            .collect::<Vec<i16>>();
+
+        if self.get_stopband_attenuation(quantized.as_slice(), fir_rate) < self.attenuation_db {
+            return Err(());
+        }
+
+        filter.rx_coefficients = quantized.clone();
+        filter.tx_coefficients = quantized;
+        filter.rx_bandwidth = Some(self.rf_bandwidth);
+        filter.tx_bandwidth = Some(self.rf_bandwidth);
+        filter.validate()?;
+        Ok(filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ftr_round_trip() {
+        let mut filter = FirFilter::new(4, 2, -6, 0, FIR_1.iter().map(|f| *f as i16).collect());
+        filter.rx_path_rates = Some(datatypes::PathRates {
+            bbpll: 983_040_000,
+            converter: 245_760_000,
+            r2: 122_880_000,
+            r1: 61_440_000,
+            rf: 30_720_000,
+            sample: 7_680_000,
+        });
+        filter.tx_bandwidth = Some(5_000_000);
+        filter.tx_coefficients[0] = -16;
+
+        let parsed = FirFilter::parse(filter.to_string().as_str()).unwrap();
+        assert_eq!(parsed, filter);
+        assert_eq!(parsed.to_string(), filter.to_string());
+    }
+
+    #[test]
+    fn ftr_single_column() {
+        let mut data = "RX 3 GAIN -6 DEC 2\n".to_owned();
+        for coefficient in FIR_4.iter() {
+            data.push_str(format!("{}\n", coefficient).as_str());
+        }
+
+        let filter = FirFilter::parse(data.as_str()).unwrap();
+        assert_eq!(filter.interpolation, 2);
+        assert_eq!(filter.tx_gain, 0);
+        assert_eq!(filter.rx_coefficients, filter.tx_coefficients);
+        assert_eq!(filter.rx_coefficients.len(), FIR_4.len());
+        assert!(filter.matches_status("FIR Rx: 64,2 Tx: 64,2").unwrap());
+    }
+
+    #[test]
+    fn clock_tap_limits() {
+        let coefficients = |taps: usize| FIR_1.iter().take(taps).map(|f| *f as i16).collect();
+        let rates = |converter: u64, sample: u64| datatypes::PathRates {
+            bbpll: 983_040_000,
+            converter,
+            r2: converter / 2,
+            r1: converter / 4,
+            rf: sample * 2,
+            sample,
+        };
+
+        // DAC at 4 times the TX rate leaves room for 64 taps
+        let mut filter = FirFilter::new(2, 2, -6, 0, coefficients(128));
+        filter.tx_path_rates = Some(rates(61_440_000, 15_360_000));
+        assert_eq!(filter.get_max_tx_taps(), 64);
+        assert_eq!(filter.get_max_rx_taps(), 128);
+        assert!(filter.validate().is_err());
+        filter.rx_coefficients = coefficients(64);
+        filter.tx_coefficients = coefficients(64);
+        assert!(filter.validate().is_ok());
+
+        // ADC at 2 times the RX rate leaves room for 32 taps
+        filter.rx_path_rates = Some(rates(61_440_000, 30_720_000));
+        assert_eq!(filter.get_max_rx_taps(), 32);
+        assert!(filter.validate().is_err());
+        filter.rx_coefficients = coefficients(32);
+        filter.tx_coefficients = coefficients(32);
+        assert!(filter.validate().is_ok());
+
+        // Without rates only the fixed limits apply
+        let filter = FirFilter::new(1, 1, 0, 0, coefficients(128));
+        assert_eq!(filter.get_max_rx_taps(), 128);
+        assert_eq!(filter.get_max_tx_taps(), 64);
+    }
+
+    #[test]
+    fn ftr_invalid() {
+        assert!(FirFilter::parse("").is_err());
+        assert!(FirFilter::parse("RX 3 GAIN -7 DEC 2\n1\n").is_err());
+        assert!(FirFilter::parse("RX 3 GAIN -6 DEC 2\n1,2,3\n").is_err());
+    }
+}
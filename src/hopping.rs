@@ -0,0 +1,148 @@
+use crate::datatypes;
+use crate::fastlock;
+use crate::traits;
+
+// Upper bound on the wait for the synthesizer lock detect after a retune
+// Each lock poll is a register read, a network context can take several ms per round trip
+pub static DEFAULT_LOCK_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(100);
+static LOCK_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_micros(500);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hop {
+    pub frequency: u64,
+    pub elapsed: std::time::Duration,
+    pub retune_latency: std::time::Duration,
+    pub fastlock: bool,
+    pub samples: Option<Vec<Vec<datatypes::PlutoComplex>>>,
+}
+
+fn lock_field(synthesizer: datatypes::Synthesizer) -> (&'static str, &'static str) {
+    match synthesizer {
+        datatypes::Synthesizer::Rx => ("rx_synth_vco_lock", "rx_synth_lock"),
+        datatypes::Synthesizer::Tx => ("tx_synth_vco_lock", "tx_synth_lock"),
+    }
+}
+
+fn set_lo<'a, T>(sdr: &T, synthesizer: datatypes::Synthesizer, frequency: u64) -> Result<(), ()>
+where
+    T: traits::AD9364<'a>,
+{
+    match synthesizer {
+        datatypes::Synthesizer::Rx => sdr.set_rx_lo(frequency),
+        datatypes::Synthesizer::Tx => sdr.set_tx_lo(frequency),
+    }
+    .map_err(|_| ())
+}
+
+fn wait_for_lock<'a, T>(
+    sdr: &mut T,
+    synthesizer: datatypes::Synthesizer,
+    timeout: std::time::Duration,
+) -> Result<(), ()>
+where
+    T: traits::Registers<'a>,
+{
+    let (register, field) = lock_field(synthesizer);
+    let start = std::time::Instant::now();
+    while sdr.read_register_field(register, field)? == 0 {
+        if start.elapsed() > timeout {
+            return Err(());
+        }
+        std::thread::sleep(LOCK_POLL_INTERVAL);
+    }
+    Ok(())
+}
+
+impl<'a, T> traits::FrequencyHopper<'a> for T
+where
+    T: traits::AD9364<'a> + traits::RxCore<'a> + traits::Registers<'a>,
+{
+    fn frequency_hop(
+        &mut self,
+        frequencies: &[u64],
+        synthesizers: &[datatypes::Synthesizer],
+        dwell: std::time::Duration,
+        cycles: usize,
+        capture: bool,
+        lock_timeout: Option<std::time::Duration>,
+    ) -> Result<Vec<Hop>, ()> {
+        if frequencies.is_empty() || synthesizers.is_empty() {
+            return Err(());
+        }
+        let lock_timeout = lock_timeout.unwrap_or(DEFAULT_LOCK_TIMEOUT);
+
+        // Short hop lists fit in the fastlock profiles 0..n, the ones previously stored
+        // through this handle are put back afterwards, any other one is lost
+        let fastlock = frequencies.len() <= fastlock::FASTLOCK_PROFILES as usize;
+        let mut saved = vec![];
+        if fastlock {
+            for index in 0..frequencies.len() {
+                for synthesizer in synthesizers {
+                    if let Some(profile) = self
+                        .get_fastlock_profiles()
+                        .get_profile(*synthesizer, index as u8)
+                    {
+                        saved.push(profile.clone());
+                    }
+                }
+            }
+        }
+
+        let mut hop = || -> Result<Vec<Hop>, ()> {
+            // Calibrate each frequency once up front and only recall the profiles while hopping
+            if fastlock {
+                for (index, frequency) in frequencies.iter().enumerate() {
+                    for synthesizer in synthesizers {
+                        set_lo(self, *synthesizer, *frequency)?;
+                        wait_for_lock(self, *synthesizer, lock_timeout)?;
+                        self.fastlock_store(*synthesizer, index as u8)?;
+                    }
+                }
+            }
+
+            let start = std::time::Instant::now();
+            let mut hops = vec![];
+            for _ in 0..cycles {
+                for (index, frequency) in frequencies.iter().enumerate() {
+                    let hop_start = std::time::Instant::now();
+                    let elapsed = start.elapsed();
+                    for synthesizer in synthesizers {
+                        if fastlock {
+                            self.fastlock_recall(*synthesizer, index as u8)?;
+                        } else {
+                            set_lo(self, *synthesizer, *frequency)?;
+                        }
+                    }
+                    for synthesizer in synthesizers {
+                        wait_for_lock(self, *synthesizer, lock_timeout)?;
+                    }
+                    let retune_latency = hop_start.elapsed();
+
+                    let samples = if capture {
+                        // Drop the buffer that may straddle the retune
+                        self.rx_complex()?;
+                        Some(self.rx_complex()?)
+                    } else {
+                        None
+                    };
+
+                    std::thread::sleep(dwell.saturating_sub(hop_start.elapsed()));
+                    hops.push(Hop {
+                        frequency: *frequency,
+                        elapsed,
+                        retune_latency,
+                        fastlock,
+                        samples,
+                    });
+                }
+            }
+            Ok(hops)
+        };
+        let hops = hop();
+
+        for profile in saved {
+            self.fastlock_load(profile)?;
+        }
+        hops
+    }
+}
@@ -1,10 +1,22 @@
 #![allow(dead_code)]
 
+pub mod attribute;
+pub mod config;
 pub mod datatypes;
 pub mod error;
+pub mod fastlock;
+pub mod fir;
+pub mod hopping;
+pub mod monitor;
 pub mod pluto;
+pub mod registers;
+pub mod sweep;
 pub mod traits;
+pub mod transaction;
+pub mod xo;
 pub extern crate iio;
 
-mod fir;
 mod types;
+
+pub use types::dds_tone::DdsTone;
+pub use types::file_sink::FileSink;
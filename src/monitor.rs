@@ -0,0 +1,63 @@
+use crate::datatypes;
+use crate::error;
+use crate::traits;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelHealth {
+    pub channel: usize,
+    pub rssi_db: f64,
+    pub gain_db: f64,
+    pub gain_control_mode: datatypes::GainControlMode,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthSample {
+    pub timestamp: std::time::SystemTime,
+    pub temperature: f64,
+    pub channels: Vec<ChannelHealth>,
+}
+
+impl<'a, T> traits::HealthMonitor<'a> for T
+where
+    T: traits::AD9364<'a>,
+{
+    fn read_health(&self, channels: &[usize]) -> Result<HealthSample, error::AdiError> {
+        let timestamp = std::time::SystemTime::now();
+        let temperature = self.get_temperature()?;
+        let mut channel_health = vec![];
+        for channel in channels {
+            channel_health.push(ChannelHealth {
+                channel: *channel,
+                rssi_db: self.get_rssi(*channel)?,
+                // In AGC modes hardwaregain reads back the gain currently applied by the AGC
+                gain_db: self.get_rx_hardwaregain(*channel)?,
+                gain_control_mode: self.get_gain_control_mode(*channel)?,
+            });
+        }
+        Ok(HealthSample {
+            timestamp,
+            temperature,
+            channels: channel_health,
+        })
+    }
+
+    fn monitor_health(
+        &self,
+        channels: &[usize],
+        interval: std::time::Duration,
+        count: usize,
+    ) -> Result<Vec<HealthSample>, error::AdiError> {
+        let start = std::time::Instant::now();
+        let mut samples = Vec::with_capacity(count);
+        for i in 0..count {
+            samples.push(self.read_health(channels)?);
+            // Schedule against the start time so slow attribute reads don't accumulate drift
+            let next = interval * (i as u32 + 1);
+            let elapsed = start.elapsed();
+            if i + 1 < count && next > elapsed {
+                std::thread::sleep(next - elapsed);
+            }
+        }
+        Ok(samples)
+    }
+}
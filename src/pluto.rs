@@ -1,11 +1,16 @@
-use std::io::Write;
-
+use crate::attribute;
+use crate::config;
 use crate::datatypes;
+use crate::error;
+use crate::fastlock;
 use crate::fir;
+use crate::registers;
 use crate::traits;
+use crate::traits::AD9364;
 use crate::traits::DDS;
 use crate::traits::RxCore;
 use crate::traits::TxCore;
+use crate::transaction;
 use crate::types;
 
 #[derive(Debug)]
@@ -22,15 +27,21 @@ pub struct Pluto<'a> {
     control_device_name: String,
     rx_data_device_name: String,
     tx_data_device_name: String,
+    fir_filter: Option<fir::FirFilter>,
+    fastlock: fastlock::FastlockStore,
+    ranges: datatypes::RadioRanges,
+    // External and powered-down LO state indexed by synthesizer, kept here so that
+    // tuning doesn't need to read it back every time
+    lo_external: [bool; 2],
+    lo_powerdown: [bool; 2],
 
     // Inherited from TxCore trait
     tx_complex_data: Option<bool>,
     tx_data_type: Option<datatypes::SdrDataType>,
     tx_cyclic_buffer: bool,
     tx_enabled_channels: Vec<u32>,
-    tx_output_byte_filename: String,
+    tx_file_sink: Option<types::file_sink::FileSink>,
     txbuf: Option<types::buffer::Buffer>,
-    tx_push_to_file: bool,
     num_tx_channels: u32,
     tx_buffer_size: usize,
 
@@ -111,6 +122,50 @@ fn implicit_convert(data: &[u8], be: bool, signed: bool, length: u32) -> Result<
     Ok(res)
 }
 
+fn write_tx_samples<T: datatypes::TxSample, I: Iterator<Item = T>>(
+    out: &mut [u8],
+    samples: I,
+) -> Result<usize, ()> {
+    let mut words = out.chunks_exact_mut(2);
+    let mut written = 0;
+    for sample in samples {
+        for component in sample.components().iter().take(T::COMPONENTS) {
+            let word = words.next().ok_or(())?;
+            word.copy_from_slice(&component.to_le_bytes());
+            written += 1;
+        }
+    }
+    Ok(written)
+}
+
+// Lowest sample rate the AD9361 clock chain can produce on its own
+static PHY_MIN_SAMPLE_RATE: u32 = 521_000;
+// Rate change of the FPGA decimator/interpolator
+static FPGA_RATE_FACTOR: u32 = 8;
+// The driver lists every AD9361 port but only the A ports are routed to the PlutoSDR SMAs
+static PLUTO_RX_PORTS: [datatypes::RxPort; 1] = [datatypes::RxPort::ABalanced];
+static PLUTO_TX_PORTS: [datatypes::TxPort; 1] = [datatypes::TxPort::A];
+
+fn phy_channel_name(channel: usize) -> Result<&'static str, error::AdiError> {
+    ["voltage0", "voltage1"]
+        .get(channel)
+        .copied()
+        .ok_or(error::AdiError::RangeError {
+            value: channel.to_string(),
+            available: "[0 1 1]".to_owned(),
+        })
+}
+
+// Gains are read back with their unit, e.g. "-10.000000 dB"
+fn parse_gain(value: &str) -> Result<f64, ()> {
+    value
+        .split_whitespace()
+        .next()
+        .ok_or(())?
+        .parse::<f64>()
+        .map_err(|_| ())
+}
+
 // En fait ce qu'il faut c'est des impl<'a, T> traits::...<'a> for T where T: traits::...<'a>
 // Le problème c'est qu'il faut des getters dans tous les sens et j'ai une flemme monstrueuse de faire ça maintenant
 
@@ -145,7 +200,11 @@ impl<'a> Pluto<'a> {
         // Unused by ad9364
         // let (run_rx_post_init, run_tx_post_init) = <Pluto<'a> as traits::RxTxDef>::init();
 
-        let split_cores = <Pluto<'a> as traits::DDS<'a>>::init();
+        let split_cores = <Pluto<'a> as traits::DDS<'a>>::init(&context, &tx_data_device_name)?;
+
+        let ranges = Pluto::read_radio_ranges(&context, &ctrl)?;
+        let lo_external = Pluto::read_lo_state(&context, &ctrl, "external")?;
+        let lo_powerdown = Pluto::read_lo_state(&context, &ctrl, "powerdown")?;
 
         let (rxadc, rx_channel_names) = <Pluto<'a> as traits::RxDef<'a>>::init(
             &context,
@@ -181,9 +240,8 @@ impl<'a> Pluto<'a> {
             tx_data_type,
             tx_cyclic_buffer,
             tx_enabled_channels,
-            tx_output_byte_filename,
+            tx_file_sink,
             txbuf,
-            tx_push_to_file,
             num_tx_channels,
             tx_buffer_size,
         ) = <Pluto<'a> as traits::TxCore<'a>>::init(None, complex_data, &tx_channel_names)?;
@@ -197,6 +255,11 @@ impl<'a> Pluto<'a> {
             control_device_name,
             rx_data_device_name,
             tx_data_device_name,
+            fir_filter: None,
+            fastlock: fastlock::FastlockStore::new(),
+            ranges,
+            lo_external,
+            lo_powerdown,
 
             rx_complex_data,
             rx_data_type,
@@ -215,9 +278,8 @@ impl<'a> Pluto<'a> {
             tx_data_type,
             tx_cyclic_buffer,
             tx_enabled_channels,
-            tx_output_byte_filename,
+            tx_file_sink,
             txbuf,
-            tx_push_to_file,
             num_tx_channels,
             tx_buffer_size,
 
@@ -231,21 +293,409 @@ impl<'a> Pluto<'a> {
             split_cores,
         })
     }
+
+    // Ranges are read once at connect time, the chip model only fills in
+    // what an older driver does not report through *_available
+    fn read_radio_ranges(
+        context: &types::context::Context,
+        ctrl: &types::device_trigger::DeviceTrigger,
+    ) -> Result<datatypes::RadioRanges, ()> {
+        // The board model names the fitted chip, the driver model may only name the
+        // driver variant (a Pluto can report ad9364 while fitted with an AD9363A)
+        let attrs = context.get_attrs();
+        let chip = ["hw_model", "ad9361-phy,model"]
+            .iter()
+            .filter_map(|f| attrs.get(f))
+            .find_map(|f| datatypes::ChipModel::from_value(f).ok());
+        // Fall back to the AD9363, it has the narrowest ranges
+        let chip_detected = chip.is_some();
+        let chip = chip.unwrap_or(datatypes::ChipModel::AD9363);
+        let iio_device = context.get_iio_context().find_device(ctrl.get_name())?;
+        let read_range = |channel_name: &str, output: bool, attr: &str| {
+            let mut channel = ctrl
+                .find_channel(iio_device, channel_name, Some(output))
+                .ok()?;
+            let iio_channel = iio_device.find_channel(channel_name, output).ok()?;
+            let attrs = channel.get_attrs();
+            let value = attrs.get(attr)?.get_value(iio_channel).ok()?;
+            datatypes::AttrRange::parse(value.as_str()).ok()
+        };
+        Ok(datatypes::RadioRanges {
+            chip,
+            chip_detected,
+            rx_lo: read_range("altvoltage0", true, "frequency_available")
+                .unwrap_or(chip.get_lo_range()),
+            tx_lo: read_range("altvoltage1", true, "frequency_available")
+                .unwrap_or(chip.get_lo_range()),
+            rx_rf_bandwidth: read_range("voltage0", false, "rf_bandwidth_available")
+                .unwrap_or(chip.get_rf_bandwidth_range()),
+            tx_rf_bandwidth: read_range("voltage0", true, "rf_bandwidth_available")
+                .unwrap_or(chip.get_rf_bandwidth_range()),
+        })
+    }
+
+    // Drivers without the attribute have neither external LOs nor LO power-down
+    fn read_lo_state(
+        context: &types::context::Context,
+        ctrl: &types::device_trigger::DeviceTrigger,
+        attr: &str,
+    ) -> Result<[bool; 2], ()> {
+        let iio_device = context.get_iio_context().find_device(ctrl.get_name())?;
+        let read_state = |synthesizer: datatypes::Synthesizer| {
+            let channel_name = synthesizer.get_channel_name();
+            let mut channel = ctrl
+                .find_channel(iio_device, channel_name, Some(true))
+                .ok()?;
+            let iio_channel = iio_device.find_channel(channel_name, true).ok()?;
+            let attrs = channel.get_attrs();
+            let value = attrs.get(attr)?.get_value(iio_channel).ok()?;
+            Some(value.trim() != "0")
+        };
+        Ok([
+            read_state(datatypes::Synthesizer::Rx).unwrap_or(false),
+            read_state(datatypes::Synthesizer::Tx).unwrap_or(false),
+        ])
+    }
+
+    // Setup shared by every TX path: sample format, DDS disable and buffer creation
+    fn tx_setup(&mut self, frames: usize) -> Result<(), ()> {
+        if frames == 0 {
+            return Err(());
+        }
+
+        if self.tx_data_type.is_none() {
+            let iio_context = self.context.as_ref().get_iio_context();
+            let iio_txdac = iio_context.find_device(&self.tx_data_device_name)?;
+            let channel_name = self.tx_channel_names.as_ref().ok_or(())?
+                [*self.tx_enabled_channels.first().ok_or(())? as usize]
+                .as_str();
+            let iio_channel = iio_txdac.find_channel(channel_name, true)?;
+            let _channel_format = iio_channel.get_data_format().ok_or(())?;
+            // TODO: Handle this logic and add data types
+
+            self.tx_data_type = Some(datatypes::SdrDataType::Int16);
+        };
+
+        // This used to be a .unwrap(), may it be remembered as the last one in this program
+        let _tx_data_type = self.tx_data_type.as_ref().ok_or(())?;
+
+        // Samples only going to a file leave the DDS and the TX buffer alone
+        if self.tx_file_sink.as_ref().is_some_and(|f| !f.get_tee()) {
+            return Ok(());
+        }
+
+        if self.txbuf.is_some() && self.tx_cyclic_buffer {
+            return Err(());
+        };
+
+        if self.txbuf.is_none() {
+            <Pluto<'a> as DDS<'a>>::disable_dds(self)?;
+            self.tx_buffer_size = frames;
+            <Pluto<'a> as types::traits::Ctx>::tx_init_channels(self)?;
+        };
+
+        if frames != self.tx_buffer_size {
+            return Err(());
+        };
+        Ok(())
+    }
+
+    fn get_data_device(&self, output: bool) -> Result<&types::device_trigger::DeviceTrigger, ()> {
+        let device = if output { &self.txdac } else { &self.rxadc };
+        device.as_deref().ok_or(())
+    }
+
+    fn read_transaction_attr(&mut self, write: &transaction::AttrWrite) -> Result<String, ()> {
+        match &write.target {
+            transaction::AttrTarget::Channel {
+                channel_name,
+                output,
+            } => traits::Attribute::get_iio_attr_str(
+                self,
+                channel_name,
+                write.attr_name.as_str(),
+                Some(*output),
+                None,
+            ),
+            transaction::AttrTarget::DataChannel {
+                channel_name,
+                output,
+            } => traits::Attribute::get_iio_attr_str(
+                self,
+                channel_name,
+                write.attr_name.as_str(),
+                Some(*output),
+                Some(self.get_data_device(*output)?),
+            ),
+            transaction::AttrTarget::Device => {
+                traits::Attribute::get_iio_dev_attr_str(self, write.attr_name.as_str(), None)
+            }
+            transaction::AttrTarget::Debug => {
+                traits::Attribute::get_iio_debug_attr_str(self, write.attr_name.as_str(), None)
+            }
+        }
+    }
+
+    fn write_transaction_attr(
+        &mut self,
+        write: &transaction::AttrWrite,
+        value: &str,
+    ) -> Result<(), ()> {
+        match &write.target {
+            transaction::AttrTarget::Channel {
+                channel_name,
+                output,
+            } => traits::Attribute::set_iio_attr_str(
+                self,
+                channel_name,
+                write.attr_name.as_str(),
+                Some(*output),
+                value,
+                None,
+            ),
+            transaction::AttrTarget::DataChannel {
+                channel_name,
+                output,
+            } => traits::Attribute::set_iio_attr_str(
+                self,
+                channel_name,
+                write.attr_name.as_str(),
+                Some(*output),
+                value,
+                Some(self.get_data_device(*output)?),
+            ),
+            transaction::AttrTarget::Device => {
+                traits::Attribute::set_iio_dev_attr_str(self, write.attr_name.as_str(), value, None)
+            }
+            transaction::AttrTarget::Debug => traits::Attribute::set_iio_debug_attr_str(
+                self,
+                write.attr_name.as_str(),
+                value,
+                None,
+            ),
+        }
+    }
+
+    fn read_descriptor_attr(
+        &mut self,
+        descriptor: &attribute::AttrDescriptor,
+        attr_name: &str,
+    ) -> Result<String, ()> {
+        match descriptor.channel_name {
+            Some(channel_name) => traits::Attribute::get_iio_attr_str(
+                self,
+                channel_name,
+                attr_name,
+                Some(descriptor.output),
+                None,
+            ),
+            None => traits::Attribute::get_iio_dev_attr_str(self, attr_name, None),
+        }
+    }
+
+    fn write_descriptor_attr(
+        &mut self,
+        descriptor: &attribute::AttrDescriptor,
+        value: &str,
+    ) -> Result<(), ()> {
+        match descriptor.channel_name {
+            Some(channel_name) => traits::Attribute::set_iio_attr_str(
+                self,
+                channel_name,
+                descriptor.attr_name,
+                Some(descriptor.output),
+                value,
+                None,
+            ),
+            None => {
+                traits::Attribute::set_iio_dev_attr_str(self, descriptor.attr_name, value, None)
+            }
+        }
+    }
+
+    fn is_lo_tunable(&self, synthesizer: datatypes::Synthesizer) -> bool {
+        let index = synthesizer.get_index();
+        !self.lo_external[index] && !self.lo_powerdown[index]
+    }
+
+    // Below the AD9361 minimum, run the transceiver 8 times faster and let the
+    // FPGA decimator/interpolator bring both directions down to the requested rate
+    fn get_phy_rate(&self, value: u32) -> Result<(u32, bool), ()> {
+        let fpga_filter = value < PHY_MIN_SAMPLE_RATE;
+        if fpga_filter && (self.rxadc.is_none() || self.txdac.is_none()) {
+            return Err(());
+        }
+        let phy_rate = if fpga_filter {
+            value.checked_mul(FPGA_RATE_FACTOR).ok_or(())?
+        } else {
+            value
+        };
+        Ok((phy_rate, fpga_filter))
+    }
+
+    // Rate of the FPGA core sampling_frequency with its decimator/interpolator on or off
+    fn get_fpga_filter_rate(&self, output: bool, enable: bool) -> Result<u32, ()> {
+        let device = self.get_data_device(output)?;
+        let rates = traits::DecIntFPGAFilter::get_rates(self, device, output)?;
+        if rates.len() < 2 {
+            return Err(());
+        }
+        Ok(if enable { rates[1] } else { rates[0] })
+    }
+
+    // The core runs at the second available rate with its decimator/interpolator on,
+    // a single available rate means it has none
+    fn get_fpga_filter_en(&self, output: bool) -> Result<bool, ()> {
+        let device = self.get_data_device(output)?;
+        let rates = traits::DecIntFPGAFilter::get_rates(self, device, output)?;
+        let rate = traits::Attribute::get_iio_attr_int(
+            self,
+            "voltage0",
+            "sampling_frequency",
+            Some(output),
+            Some(device),
+        )?;
+        Ok(rates.len() >= 2 && rate == rates[1] as i128)
+    }
+
+    fn get_fpga_filter_transaction(
+        &self,
+        mut transaction: transaction::AttrTransaction,
+        enable: bool,
+    ) -> Result<transaction::AttrTransaction, ()> {
+        for output in [false, true] {
+            if self.get_data_device(output).is_ok() {
+                let rate = self.get_fpga_filter_rate(output, enable)?;
+                transaction = transaction.data_channel(
+                    "voltage0",
+                    output,
+                    "sampling_frequency",
+                    rate.to_string().as_str(),
+                );
+            }
+        }
+        Ok(transaction)
+    }
+
+    fn set_fpga_filters(&mut self, enable: bool) -> Result<(), error::AdiError> {
+        let transaction =
+            self.get_fpga_filter_transaction(transaction::AttrTransaction::new(), enable)?;
+        traits::Attribute::apply_iio_attr_transaction(self, &transaction)
+    }
+
+    fn get_phy_sample_rate(&self) -> Result<u32, ()> {
+        traits::Attribute::get_iio_attr_int(
+            self,
+            "voltage0",
+            "sampling_frequency",
+            Some(false),
+            None,
+        )
+        .map(|f| f as u32)
+    }
+
+    fn get_stock_filter(value: u32) -> fir::FirFilter {
+        let (dec, fir) = match value {
+            x if x <= 20_000_000 => (4, fir::FIR_1.as_slice()),
+            x if x <= 40_000_000 => (2, fir::FIR_2.as_slice()),
+            x if x <= 53_333_333 => (2, fir::FIR_3.as_slice()),
+            _ => (2, fir::FIR_4.as_slice()),
+        };
+        fir::FirFilter::new(dec, dec, -6, 0, fir.iter().map(|f| *f as i16).collect())
+    }
+
+    // Writes loading the stock FIR for the rate and setting the PHY rate, returned
+    // with the filter to cache once the transaction is applied
+    fn get_phy_sample_rate_transaction(
+        &self,
+        value: u32,
+    ) -> Result<(transaction::AttrTransaction, fir::FirFilter), ()> {
+        static CURRENT_SAMPLING_THRESH: u32 = 2083333;
+        static SAMPLING_FREQUENCY_FIXED: &str = "3000000";
+        if value < PHY_MIN_SAMPLE_RATE {
+            return Err(());
+        }
+        let filter = Pluto::get_stock_filter(value);
+
+        let current_sampling_freq = self.get_phy_sample_rate()?;
+        let fir_old = traits::Attribute::get_iio_attr_str(
+            self,
+            "out",
+            "voltage_filter_fir_en",
+            Some(false),
+            None,
+        )?;
+        let fir_number = fir_old.parse::<u32>().map_err(|_| ())?;
+
+        let mut transaction = transaction::AttrTransaction::new();
+        if fir_number != 0 {
+            if current_sampling_freq <= CURRENT_SAMPLING_THRESH {
+                transaction = transaction.channel(
+                    "voltage0",
+                    false,
+                    "sampling_frequency",
+                    SAMPLING_FREQUENCY_FIXED,
+                );
+            }
+            transaction = transaction.channel("out", false, "voltage_filter_fir_en", "0");
+        }
+
+        // The driver only reports the loaded taps as text, so roll back to the filter
+        // this handle loaded or, failing that, the stock one for the current rate
+        let previous_filter = match &self.fir_filter {
+            Some(previous_filter) => previous_filter.clone(),
+            None => Pluto::get_stock_filter(current_sampling_freq),
+        };
+        transaction = transaction
+            .device("filter_fir_config", filter.to_string().as_str())
+            .rollback_to(previous_filter.to_string().as_str());
+
+        let value_string = value.to_string();
+        if value <= CURRENT_SAMPLING_THRESH {
+            // The TX path ratio at the current rate may not fit the taps and it is only
+            // known once the filter is loaded, 3 MSPS always fits
+            transaction = transaction
+                .channel(
+                    "voltage0",
+                    false,
+                    "sampling_frequency",
+                    SAMPLING_FREQUENCY_FIXED,
+                )
+                .channel("out", false, "voltage_filter_fir_en", "1")
+                .channel(
+                    "voltage0",
+                    false,
+                    "sampling_frequency",
+                    value_string.as_str(),
+                );
+        } else {
+            transaction = transaction
+                .channel(
+                    "voltage0",
+                    false,
+                    "sampling_frequency",
+                    value_string.as_str(),
+                )
+                .channel("out", false, "voltage_filter_fir_en", "1");
+        }
+
+        Ok((transaction, filter))
+    }
 }
 
 impl<'a> traits::Attribute<'a> for Pluto<'a> {
     fn set_iio_attr_str(
-        &'a self,
+        &self,
         channel_name: &str,
-        attr_name: &'a str,
+        attr_name: &str,
         output: Option<bool>,
-        value: &'a str,
-        ctrl: Option<&'a types::device_trigger::DeviceTrigger>,
+        value: &str,
+        ctrl: Option<&types::device_trigger::DeviceTrigger>,
     ) -> Result<(), ()> {
         let control = ctrl.unwrap_or(&self.ctrl);
         let box_context = self.context.as_ref();
         let iio_context = box_context.get_iio_context();
-        let iio_device = iio_context.find_device("ad9361-phy")?;
+        let iio_device = iio_context.find_device(control.get_name())?;
         let iio_channel = iio_device.find_channel(channel_name, output.unwrap_or(false))?;
         let mut channel = control.find_channel(iio_device, channel_name, output)?;
         let attrs = channel.get_attrs();
@@ -255,20 +705,20 @@ impl<'a> traits::Attribute<'a> for Pluto<'a> {
     }
 
     fn get_iio_attr_str(
-        &'a self,
-        channel_name: &'a str,
-        attr_name: &'a str,
+        &self,
+        channel_name: &str,
+        attr_name: &str,
         output: Option<bool>,
-        ctrl: Option<&'a types::device_trigger::DeviceTrigger>,
+        ctrl: Option<&types::device_trigger::DeviceTrigger>,
     ) -> Result<String, ()> {
         let control = ctrl.unwrap_or(&self.ctrl);
         let iio_context = self.context.get_iio_context();
-        let iio_device = iio_context.find_device("ad9361-phy")?;
+        let iio_device = iio_context.find_device(control.get_name())?;
         let iio_channel = iio_device.find_channel(channel_name, output.unwrap_or(false))?;
         let mut channel = control.find_channel(iio_device, channel_name, output)?;
         let attrs = channel.get_attrs();
         let attr = attrs.get(attr_name).ok_or(())?;
-        Ok(attr.get_value(iio_channel)?.to_owned())
+        Ok(attr.get_value(iio_channel)?.trim().to_owned())
     }
 
     fn set_iio_dev_attr_str(
@@ -301,80 +751,125 @@ impl<'a> traits::Attribute<'a> for Pluto<'a> {
     }
 
     fn set_iio_debug_attr_str(
-        &self,
-        _attr_name: &str,
-        _value: &str,
-        _ctrl: Option<&types::device_trigger::DeviceTrigger>,
+        &mut self,
+        attr_name: &str,
+        value: &str,
+        ctrl: Option<&mut types::device_trigger::DeviceTrigger>,
     ) -> Result<(), ()> {
-        todo!()
+        let control = ctrl.unwrap_or(&mut self.ctrl);
+        let iio_context = self.context.get_iio_context();
+        let iio_device = iio_context.find_device(control.get_name())?;
+        let debug_attrs = control.get_debug_attrs();
+        let entry = debug_attrs.get_mut(attr_name).ok_or(())?;
+        let result = entry.set_value(iio_device, value);
+        if result <= 0 { Err(()) } else { Ok(()) }
     }
 
     fn get_iio_debug_attr_str(
-        &self,
-        _attr_name: &str,
-        _ctrl: Option<&types::device_trigger::DeviceTrigger>,
+        &mut self,
+        attr_name: &str,
+        ctrl: Option<&mut types::device_trigger::DeviceTrigger>,
     ) -> Result<String, ()> {
-        todo!()
+        let control = ctrl.unwrap_or(&mut self.ctrl);
+        let iio_context = self.context.get_iio_context();
+        let iio_device = iio_context.find_device(control.get_name())?;
+        let debug_attrs = control.get_debug_attrs();
+        let attr = debug_attrs.get(attr_name).ok_or(())?;
+        Ok(attr.get_value(iio_device)?.to_owned())
     }
 
     fn set_iio_attr_int<T: Into<i128>>(
-        &'a self,
+        &self,
         channel_name: &str,
-        attr_name: &'a str,
+        attr_name: &str,
         output: Option<bool>,
         value: T,
-        ctrl: Option<&'a types::device_trigger::DeviceTrigger>,
+        ctrl: Option<&types::device_trigger::DeviceTrigger>,
     ) -> Result<(), ()> {
         let value_string = format!("{:}", value.into());
         self.set_iio_attr_str(channel_name, attr_name, output, value_string.as_str(), ctrl)
     }
 
     fn get_iio_attr_int(
-        &'a self,
-        channel_name: &'a str,
-        attr_name: &'a str,
+        &self,
+        channel_name: &str,
+        attr_name: &str,
         output: Option<bool>,
-        ctrl: Option<&'a types::device_trigger::DeviceTrigger>,
+        ctrl: Option<&types::device_trigger::DeviceTrigger>,
     ) -> Result<i128, ()> {
         let res = self.get_iio_attr_str(channel_name, attr_name, output, ctrl)?;
-        let bytes_with_dot = res
-            .as_bytes()
-            .iter()
-            .take_while(|f| f.is_ascii_digit())
-            .copied()
-            .collect::<Vec<u8>>();
-        let bytes_string = String::from_utf8_lossy(bytes_with_dot.as_slice()).to_string();
-        bytes_string.parse::<i128>().map_err(|_| ())
+        attribute::parse_int(res.as_str())
     }
 
     fn set_iio_attr_float<T: Into<f64>>(
-        &'a self,
+        &self,
         channel_name: &str,
-        attr_name: &'a str,
+        attr_name: &str,
         output: Option<bool>,
         value: T,
-        ctrl: Option<&'a types::device_trigger::DeviceTrigger>,
+        ctrl: Option<&types::device_trigger::DeviceTrigger>,
     ) -> Result<(), ()> {
-        let value_string = format!("{:.1}", value.into());
+        let value_string = format!("{}", value.into());
         self.set_iio_attr_str(channel_name, attr_name, output, value_string.as_str(), ctrl)
     }
 
     fn get_iio_attr_float(
-        &'a self,
-        channel_name: &'a str,
-        attr_name: &'a str,
+        &self,
+        channel_name: &str,
+        attr_name: &str,
         output: Option<bool>,
-        ctrl: Option<&'a types::device_trigger::DeviceTrigger>,
+        ctrl: Option<&types::device_trigger::DeviceTrigger>,
     ) -> Result<f64, ()> {
         let res = self.get_iio_attr_str(channel_name, attr_name, output, ctrl)?;
-        let bytes_with_dot = res
-            .as_bytes()
-            .iter()
-            .take_while(|f| f.is_ascii_digit() || **f == b'.')
-            .copied()
-            .collect::<Vec<u8>>();
-        let bytes_string = String::from_utf8_lossy(bytes_with_dot.as_slice()).to_string();
-        bytes_string.parse::<f64>().map_err(|_| ())
+        attribute::parse_float(res.as_str())
+    }
+
+    fn apply_iio_attr_transaction(
+        &mut self,
+        transaction: &transaction::AttrTransaction,
+    ) -> Result<(), error::AdiError> {
+        transaction.apply(
+            self,
+            Pluto::read_transaction_attr,
+            Pluto::write_transaction_attr,
+        )
+    }
+
+    fn get_iio_attr_available(
+        &mut self,
+        descriptor: &attribute::AttrDescriptor,
+    ) -> Result<Option<attribute::AttrAvailable>, ()> {
+        let Some(available) = descriptor.available else {
+            return Ok(None);
+        };
+        let value = self.read_descriptor_attr(descriptor, available)?;
+        Ok(Some(attribute::AttrAvailable::parse(value.as_str())))
+    }
+
+    fn get_iio_attr_typed(
+        &mut self,
+        descriptor: &attribute::AttrDescriptor,
+    ) -> Result<attribute::AttrValue, ()> {
+        let value = self.read_descriptor_attr(descriptor, descriptor.attr_name)?;
+        attribute::AttrValue::parse(descriptor.value_type, value.as_str())
+    }
+
+    fn set_iio_attr_typed(
+        &mut self,
+        descriptor: &attribute::AttrDescriptor,
+        value: attribute::AttrValue,
+    ) -> Result<(), error::AdiError> {
+        if value.get_value_type() != descriptor.value_type {
+            return Err(error::AdiError::TypeMismatchError {
+                expected: descriptor.value_type,
+                found: value.get_value_type(),
+            });
+        }
+        if let Some(available) = traits::Attribute::get_iio_attr_available(self, descriptor)? {
+            available.check(&value)?;
+        }
+        self.write_descriptor_attr(descriptor, value.get_string().as_str())?;
+        Ok(())
     }
 }
 
@@ -583,36 +1078,83 @@ impl<'a> types::traits::Crx for Pluto<'a> {
     }
 }
 
+// Additional DDS cores driving the TX channels that follow the main core's
+fn find_secondary_dds_cores(
+    iio_context: &iio::IIOContext,
+    tx_data_device_name: &str,
+) -> Result<Vec<String>, ()> {
+    let mut device_names = vec![];
+    for index in 0..iio_context.get_devices_count() {
+        let iio_device = iio_context.get_device(index).ok_or(())?;
+        let device_name = iio_device.get_name().map_err(|_| ())?;
+        if device_name != tx_data_device_name && device_name.starts_with("cf-ad9361-dds-core") {
+            device_names.push(device_name);
+        }
+    }
+    Ok(device_names)
+}
+
 impl<'a> traits::DDS<'a> for Pluto<'a> {
-    fn init() -> bool {
-        true
+    fn init(context: &types::context::Context, tx_data_device_name: &str) -> Result<bool, ()> {
+        let secondary = find_secondary_dds_cores(context.get_iio_context(), tx_data_device_name)?;
+        Ok(!secondary.is_empty())
     }
 
-    fn update_dds(&self, attr: &str, value: Vec<types::traits::DdsValue>) -> Result<(), ()> {
-        let _tx_channel_names = self.tx_channel_names.clone().ok_or(())?;
-        let txdac = self.txdac.as_ref().ok_or(())?;
+    fn get_dds_tones(&self) -> Result<Vec<types::dds_tone::DdsTone>, ()> {
         let iio_context = self.context.as_ref().get_iio_context();
-        let iio_txdac = iio_context.find_device(&self.tx_data_device_name)?;
-        let channels = txdac.get_channels(iio_txdac)?;
-        let mut _split_cores_indx = 0;
-        for index in 0..channels.len() {
-            let channel_name_string = "altvoltage".to_owned() + index.to_string().as_str();
-            let channel_name = channel_name_string.as_str();
-            let channel = txdac.find_channel(iio_txdac, channel_name, Some(true));
-            if channel.is_err() {
-                // TODO: This should be implemented
-                return Ok(());
-            }
-            let mut final_channel = channel?;
-            if index >= value.len() {
-                return Ok(());
+        let mut device_names = vec![self.tx_data_device_name.clone()];
+        if self.split_cores {
+            device_names.append(&mut find_secondary_dds_cores(
+                iio_context,
+                self.tx_data_device_name.as_str(),
+            )?);
+        }
+
+        let mut tones: Vec<types::dds_tone::DdsTone> = vec![];
+        for device_name in device_names {
+            let txdac = self.context.find_device(device_name.as_str())?;
+            let iio_txdac = iio_context.find_device(device_name.as_str())?;
+            // Each additional core drives the TX channels following the previous core's, this
+            // only applies to the index fallback as labels already hold the global channel
+            let tx_channel_offset = tones
+                .iter()
+                .map(|f| f.get_tx_channel() + 1)
+                .max()
+                .unwrap_or(0);
+            let mut core_tones = vec![];
+            for channel in txdac.get_channels(iio_txdac)? {
+                if !channel.get_is_output() || !channel.get_id().starts_with("altvoltage") {
+                    continue;
+                }
+                core_tones.push(types::dds_tone::DdsTone::new(
+                    device_name.clone(),
+                    channel.get_id().to_owned(),
+                    channel.get_name().map(|f| f.to_owned()),
+                    tx_channel_offset,
+                )?);
             }
-            let iio_channel = iio_txdac.find_channel(channel_name, true)?;
-            let attrs = final_channel.get_attrs();
+            tones.append(&mut core_tones);
+        }
+
+        tones.sort_by_key(|f| (f.get_tx_channel(), f.get_quadrature(), f.get_tone()));
+        Ok(tones)
+    }
+
+    fn update_dds(&self, attr: &str, value: Vec<types::traits::DdsValue>) -> Result<(), ()> {
+        let tones = <Pluto<'a> as DDS<'a>>::get_dds_tones(self)?;
+        if value.len() > tones.len() {
+            return Err(());
+        }
+        let iio_context = self.context.as_ref().get_iio_context();
+        for (tone, value) in std::iter::zip(tones.iter(), value.iter()) {
+            let txdac = self.context.find_device(tone.get_device_name())?;
+            let iio_txdac = iio_context.find_device(tone.get_device_name())?;
+            let mut channel = txdac.find_channel(iio_txdac, tone.get_channel_id(), Some(true))?;
+            let iio_channel = iio_txdac.find_channel(tone.get_channel_id(), true)?;
+            let attrs = channel.get_attrs();
             let entry = attrs.get_mut(attr).ok_or(())?;
-            let result = entry.set_value(iio_channel, value[index].get_string().as_str());
-            if result <= 0
-            {
+            let result = entry.set_value(iio_channel, value.get_string().as_str());
+            if result <= 0 {
                 return Err(());
             }
         }
@@ -620,60 +1162,171 @@ impl<'a> traits::DDS<'a> for Pluto<'a> {
         Ok(())
     }
 
-    fn read_dds(&self, _attr: &str) -> &str {
-        todo!()
-    }
+    fn read_dds(&self, attr: &str) -> Result<Vec<String>, ()> {
+        let tones = <Pluto<'a> as DDS<'a>>::get_dds_tones(self)?;
+        let iio_context = self.context.as_ref().get_iio_context();
+        let mut values = vec![];
+        for tone in tones.iter() {
+            let txdac = self.context.find_device(tone.get_device_name())?;
+            let iio_txdac = iio_context.find_device(tone.get_device_name())?;
+            let mut channel = txdac.find_channel(iio_txdac, tone.get_channel_id(), Some(true))?;
+            let iio_channel = iio_txdac.find_channel(tone.get_channel_id(), true)?;
+            let attrs = channel.get_attrs();
+            let entry = attrs.get(attr).ok_or(())?;
+            values.push(entry.get_value(iio_channel)?.trim().to_owned());
+        }
+
+        Ok(values)
+    }
 
     fn disable_dds(&self) -> Result<(), ()> {
-        let value = vec![false; (self.num_tx_channels * 2) as usize];
-        <Pluto<'a> as DDS<'a>>::set_dds_enabled(self, value)
+        let num_dds = <Pluto<'a> as DDS<'a>>::get_dds_tones(self)?.len();
+        <Pluto<'a> as DDS<'a>>::set_dds_enabled(self, vec![false; num_dds])
     }
 
-    fn get_dds_frequencies(&self) -> &str {
-        todo!()
+    fn get_dds_frequencies(&self) -> Result<Vec<u64>, ()> {
+        <Pluto<'a> as DDS<'a>>::read_dds(self, "frequency")?
+            .iter()
+            .map(|f| f.parse::<u64>().map_err(|_| ()))
+            .collect()
     }
 
-    fn set_dds_frequencies(&self, _value: &str) {
-        todo!()
+    fn set_dds_frequencies(&self, value: Vec<u64>) -> Result<(), ()> {
+        let values = value.iter().map(|f| *f as i128).collect::<Vec<i128>>();
+        <Pluto<'a> as DDS<'a>>::update_dds(
+            self,
+            "frequency",
+            values.iter().map(|f| f.into()).collect(),
+        )
     }
 
-    fn get_dds_scales(&self) -> &str {
-        todo!()
+    fn get_dds_scales(&self) -> Result<Vec<f64>, ()> {
+        <Pluto<'a> as DDS<'a>>::read_dds(self, "scale")?
+            .iter()
+            .map(|f| f.parse::<f64>().map_err(|_| ()))
+            .collect()
     }
 
-    fn set_dds_scales(&self, _value: &str) {
-        todo!()
+    fn set_dds_scales(&self, value: Vec<f64>) -> Result<(), ()> {
+        if value.iter().any(|f| !(0.0..=1.0).contains(f)) {
+            return Err(());
+        }
+        <Pluto<'a> as DDS<'a>>::update_dds(self, "scale", value.iter().map(|f| f.into()).collect())
     }
 
-    fn get_dds_phases(&self) -> &str {
-        todo!()
+    fn get_dds_phases(&self) -> Result<Vec<u32>, ()> {
+        <Pluto<'a> as DDS<'a>>::read_dds(self, "phase")?
+            .iter()
+            .map(|f| f.parse::<u32>().map_err(|_| ()))
+            .collect()
     }
 
-    fn set_dds_phases(&self, _value: &str) {
-        todo!()
+    fn set_dds_phases(&self, value: Vec<u32>) -> Result<(), ()> {
+        let values = value
+            .iter()
+            .map(|f| (*f % 360_000) as i128)
+            .collect::<Vec<i128>>();
+        <Pluto<'a> as DDS<'a>>::update_dds(self, "phase", values.iter().map(|f| f.into()).collect())
     }
 
-    fn get_dds_enabled(&self) -> &str {
-        todo!()
+    fn get_dds_phases_radians(&self) -> Result<Vec<f64>, ()> {
+        let phases = <Pluto<'a> as DDS<'a>>::get_dds_phases(self)?;
+        Ok(phases
+            .iter()
+            .map(|f| (*f as f64 / 1000.0).to_radians())
+            .collect())
+    }
+
+    fn set_dds_phases_radians(&self, value: Vec<f64>) -> Result<(), ()> {
+        let phases = value
+            .iter()
+            .map(|f| (f.to_degrees().rem_euclid(360.0) * 1000.0).round() as u32)
+            .collect();
+        <Pluto<'a> as DDS<'a>>::set_dds_phases(self, phases)
+    }
+
+    fn get_dds_enabled(&self) -> Result<Vec<bool>, ()> {
+        <Pluto<'a> as DDS<'a>>::read_dds(self, "raw")?
+            .iter()
+            .map(|f| f.parse::<u32>().map(|f| f != 0).map_err(|_| ()))
+            .collect()
     }
 
     fn set_dds_enabled(&self, value: Vec<bool>) -> Result<(), ()> {
         <Pluto<'a> as DDS<'a>>::update_dds(self, "raw", value.iter().map(|f| f.into()).collect())
     }
 
-    fn dds_single_tone(&self, _frequency: i32, _scale: f32, _channel: i32) {
-        todo!()
+    // The DDS runs at the DAC core rate, which differs from the PHY rate
+    // once the FPGA interpolator is on
+    fn get_dds_sample_rate(&self) -> Result<u64, ()> {
+        let txdac = self.txdac.as_ref().ok_or(())?;
+        let sample_rate = traits::Attribute::get_iio_attr_int(
+            self,
+            "voltage0",
+            "sampling_frequency",
+            Some(true),
+            Some(txdac),
+        )?;
+        u64::try_from(sample_rate).map_err(|_| ())
+    }
+
+    fn dds_single_tone(&mut self, frequency: i32, scale: f32, channel: i32) -> Result<(), ()> {
+        <Pluto<'a> as DDS<'a>>::dds_dual_tone(self, frequency, scale, 0, 0.0, channel)
     }
 
     fn dds_dual_tone(
-        &self,
-        _frequency1: i32,
-        _scale1: f32,
-        _frequncy2: i32,
-        _scale2: f32,
-        _channel: i32,
-    ) {
-        todo!()
+        &mut self,
+        frequency1: i32,
+        scale1: f32,
+        frequency2: i32,
+        scale2: f32,
+        channel: i32,
+    ) -> Result<(), ()> {
+        let tones = <Pluto<'a> as DDS<'a>>::get_dds_tones(self)?;
+        if channel < 0 || !tones.iter().any(|f| f.get_tx_channel() == channel as usize) {
+            return Err(());
+        }
+
+        let sample_rate = <Pluto<'a> as DDS<'a>>::get_dds_sample_rate(self)?;
+        if [frequency1, frequency2]
+            .iter()
+            .any(|f| f.unsigned_abs() as u64 > sample_rate / 2)
+        {
+            return Err(());
+        }
+        if [scale1, scale2].iter().any(|f| !(0.0..=1.0).contains(f)) {
+            return Err(());
+        }
+
+        <Pluto<'a> as TxCore<'a>>::tx_destroy_buffer(self);
+
+        let num_dds = tones.len();
+        let mut frequencies = vec![0; num_dds];
+        let mut scales = vec![0.0; num_dds];
+        let mut phases = vec![0; num_dds];
+        let mut enabled = vec![false; num_dds];
+        for (index, tone) in tones.iter().enumerate() {
+            if tone.get_tx_channel() != channel as usize || tone.get_tone() > 1 {
+                continue;
+            }
+            enabled[index] = true;
+            let (frequency, scale) = if tone.get_tone() == 0 {
+                (frequency1, scale1)
+            } else {
+                (frequency2, scale2)
+            };
+            // I leads Q by 90 degrees, a negative frequency is obtained by making it lag instead
+            let phase_i = if frequency < 0 { 270_000 } else { 90_000 };
+            frequencies[index] = frequency.unsigned_abs() as u64;
+            scales[index] = scale as f64;
+            phases[index] = if tone.get_quadrature() { 0 } else { phase_i };
+        }
+
+        // Tones of the other channels are disabled and zeroed
+        <Pluto<'a> as DDS<'a>>::set_dds_frequencies(self, frequencies)?;
+        <Pluto<'a> as DDS<'a>>::set_dds_scales(self, scales)?;
+        <Pluto<'a> as DDS<'a>>::set_dds_phases(self, phases)?;
+        <Pluto<'a> as DDS<'a>>::set_dds_enabled(self, enabled)
     }
 }
 
@@ -759,7 +1412,7 @@ impl<'a> traits::RxCore<'a> for Pluto<'a> {
     }
 
     fn rx_destroy_buffer(&mut self) {
-        todo!()
+        self.rxbuf = None;
     }
 
     fn get_rx_channel_scales(&self) -> Vec<f32> {
@@ -903,6 +1556,77 @@ impl<'a> types::traits::Ctx for Pluto<'a> {
         txbuf.push(None);
         Ok(())
     }
+
+    fn tx_buffer_write<T: datatypes::TxSample, I: Iterator<Item = T>>(
+        &mut self,
+        frames: usize,
+        samples: I,
+    ) -> Result<(), ()> {
+        let n = if <Pluto<'a> as TxCore<'a>>::get_tx_complex_data(self) {
+            2
+        } else {
+            1
+        };
+        let stride = self.tx_enabled_channels.len() * n;
+        if stride == 0 || frames == 0 {
+            return Err(());
+        }
+
+        let words = frames * stride;
+        let (sample_rate, frequency) = match &self.tx_file_sink {
+            Some(sink) if sink.get_format() == datatypes::TxFileFormat::SigMF => (
+                <Pluto<'a> as traits::AD9364<'a>>::get_sample_rate(self).ok(),
+                <Pluto<'a> as traits::AD9364<'a>>::get_tx_lo(self).ok(),
+            ),
+            _ => (None, None),
+        };
+        let num_tx_channels_enabled = self.tx_enabled_channels.len();
+        let complex_data = n == 2;
+
+        match self.tx_file_sink.as_mut() {
+            Some(sink) if !sink.get_tee() => {
+                let mut out_data = vec![0; words * 2];
+                if write_tx_samples(out_data.as_mut_slice(), samples)? != words {
+                    return Err(());
+                }
+                sink.write(
+                    out_data.as_slice(),
+                    num_tx_channels_enabled,
+                    complex_data,
+                    sample_rate,
+                    frequency,
+                )?;
+            }
+            sink_opt => {
+                // tx_setup must have sized the buffer for these frames
+                if frames != self.tx_buffer_size {
+                    return Err(());
+                }
+                let txbuf = self.txbuf.as_mut().ok_or(())?;
+                let buffer = txbuf.as_mut_slice();
+                if buffer.len() < words * 2 {
+                    return Err(());
+                }
+                if write_tx_samples(&mut buffer[..words * 2], samples)? != words {
+                    return Err(());
+                }
+                // Copy before pushing, a failing sink must not hold back the radio
+                let tee_data = sink_opt.as_ref().map(|_| buffer[..words * 2].to_vec());
+                txbuf.push(None);
+                if let (Some(sink), Some(tee_data)) = (sink_opt, tee_data) {
+                    sink.write(
+                        tee_data.as_slice(),
+                        num_tx_channels_enabled,
+                        complex_data,
+                        sample_rate,
+                        frequency,
+                    )?;
+                }
+            }
+        };
+
+        Ok(())
+    }
 }
 
 impl<'a> traits::TxCore<'a> for Pluto<'a> {
@@ -915,9 +1639,8 @@ impl<'a> traits::TxCore<'a> for Pluto<'a> {
         let tx_complex_data = None;
         let tx_data_type = Some(datatypes::SdrDataType::Int16);
         let tx_cyclic_buffer = in_tx_cyclic_buffer.unwrap_or(false);
-        let tx_output_byte_filename = "out.bin".to_owned();
+        let tx_file_sink = None;
         let txbuf = None;
-        let tx_push_to_file = false;
         let n = if complex_data { 2u32 } else { 1u32 };
         let num_tx_channels = in_tx_channel_names.len() as u32;
         let tx_enabled_channels_range = num_tx_channels / n;
@@ -928,9 +1651,8 @@ impl<'a> traits::TxCore<'a> for Pluto<'a> {
             tx_data_type,
             tx_cyclic_buffer,
             tx_enabled_channels,
-            tx_output_byte_filename,
+            tx_file_sink,
             txbuf,
-            tx_push_to_file,
             num_tx_channels,
             tx_buffer_size,
         ))
@@ -963,8 +1685,16 @@ impl<'a> traits::TxCore<'a> for Pluto<'a> {
         todo!()
     }
 
+    fn get_tx_file_sink(&self) -> Option<&types::file_sink::FileSink> {
+        self.tx_file_sink.as_ref()
+    }
+
+    fn set_tx_file_sink(&mut self, value: Option<types::file_sink::FileSink>) {
+        self.tx_file_sink = value;
+    }
+
     fn tx_destroy_buffer(&mut self) {
-        todo!()
+        self.txbuf = None;
     }
 
     fn tx(&mut self, data_opt: Option<Vec<Vec<datatypes::PlutoComplex>>>) -> Result<(), ()>
@@ -993,20 +1723,6 @@ impl<'a> traits::TxCore<'a> for Pluto<'a> {
             return Err(());
         };
 
-        if self.tx_data_type.is_none() {
-            let channel_name = self.tx_channel_names.as_ref().ok_or(())?
-                [self.tx_enabled_channels[0] as usize]
-                .as_str();
-            let iio_channel = iio_txdac.find_channel(channel_name, true)?;
-            let _channel_format = iio_channel.get_data_format().ok_or(())?;
-            // TODO: Handle this logic and add data types
-
-            self.tx_data_type = Some(datatypes::SdrDataType::Int16);
-        };
-
-        // This used to be a .unwrap(), may it be remembered as the last one in this program
-        let _tx_data_type = self.tx_data_type.as_ref().ok_or(())?;
-
         let data = data_opt.ok_or(())?;
         let mut _index = 0;
         let num_tx_channels_enabled = <Pluto<'a> as TxCore<'a>>::get_num_tx_channels_enabled(self);
@@ -1015,62 +1731,63 @@ impl<'a> traits::TxCore<'a> for Pluto<'a> {
             return Err(());
         }
 
-        if self.txbuf.is_some() && self.tx_cyclic_buffer {
-            return Err(());
-        };
-
-        // TODO: C'est pas fini hein petit bâtard
-
-        let (stride, out_data): (usize, Vec<u8>) =
-            if <Pluto<'a> as TxCore<'a>>::get_tx_complex_data(self) {
-                let stride = num_tx_channels_enabled * 2;
-                let mut out_data = vec![0; stride * data[0].len() * 2];
-                for (channel_index, channel_data) in data.iter().enumerate() {
-                    let real_values = channel_data.iter().map(|f| (f.re as i128) << 14);
-                    let imaginary_values = channel_data.iter().map(|f| (f.im as i128) << 14);
-                    for (index, real) in real_values.enumerate() {
-                        let real_bytes = (real as u16).to_le_bytes();
-                        out_data[((channel_index * 2 + index) * 2) * 2] = real_bytes[0];
-                        out_data[((channel_index * 2 + index) * 2) * 2 + 1] = real_bytes[1];
-                    }
-                    for (index, imaginary) in imaginary_values.enumerate() {
-                        let imaginary_bytes = (imaginary as u16).to_le_bytes();
-                        out_data[((channel_index * 2 + index) * 2 + 1) * 2] = imaginary_bytes[0];
-                        out_data[((channel_index * 2 + index) * 2 + 1) * 2 + 1] =
-                            imaginary_bytes[1];
-                    }
-                }
-
-                (stride, out_data)
-            } else {
-                let _stride = num_tx_channels_enabled;
-                todo!()
-            };
+        let slices = data
+            .iter()
+            .map(|f| f.as_slice())
+            .collect::<Vec<&[datatypes::PlutoComplex]>>();
+        <Pluto<'a> as TxCore<'a>>::tx_from_slices(self, slices.as_slice())
+    }
 
-        if self.txbuf.is_none() {
-            <Pluto<'a> as DDS<'a>>::disable_dds(self)?;
-            self.tx_buffer_size = out_data.len() / stride / 2;
-            <Pluto<'a> as TxCore<'a>>::tx_init_channels(self)?;
+    fn tx_from_slices<T: datatypes::TxSample>(&mut self, data: &[&[T]]) -> Result<(), ()> {
+        let n = if <Pluto<'a> as TxCore<'a>>::get_tx_complex_data(self) {
+            2
+        } else {
+            1
         };
+        if T::COMPONENTS != n {
+            return Err(());
+        }
 
-        // println!("TX buffer size = {}", self.tx_buffer_size);
+        let num_tx_channels_enabled = <Pluto<'a> as TxCore<'a>>::get_num_tx_channels_enabled(self);
+        if data.is_empty() || data.len() != num_tx_channels_enabled {
+            return Err(());
+        }
 
-        if out_data.len() / stride / 2 != self.tx_buffer_size {
+        let frames = data[0].len();
+        if data.iter().any(|f| f.len() != frames) {
             return Err(());
-        };
+        }
+
+        self.tx_setup(frames)?;
+        let samples = (0..frames).flat_map(|index| data.iter().map(move |f| f[index]));
+        <Pluto<'a> as types::traits::Ctx>::tx_buffer_write(self, frames, samples)
+    }
 
-        // println!("Data = {:?}, {}", out_data, out_data.len());
+    fn tx_interleaved<T: datatypes::TxSample>(&mut self, data: &[T]) -> Result<(), ()> {
+        <Pluto<'a> as TxCore<'a>>::tx_interleaved_iter(self, data.iter().copied())
+    }
 
-        if self.tx_push_to_file {
-            // println!("HELO {}", self.tx_output_byte_filename.clone());
-            let mut f =
-                std::fs::File::create(self.tx_output_byte_filename.clone()).map_err(|_| ())?;
-            f.write(out_data.as_slice()).map_err(|_| ())?;
+    fn tx_interleaved_iter<T: datatypes::TxSample, I: ExactSizeIterator<Item = T>>(
+        &mut self,
+        data: I,
+    ) -> Result<(), ()> {
+        let n = if <Pluto<'a> as TxCore<'a>>::get_tx_complex_data(self) {
+            2
         } else {
-            <Pluto<'a> as TxCore<'a>>::tx_buffer_push(self, out_data)?;
+            1
         };
+        if T::COMPONENTS != n {
+            return Err(());
+        }
 
-        Ok(())
+        let stride = <Pluto<'a> as TxCore<'a>>::get_num_tx_channels_enabled(self) * n;
+        let words = data.len() * T::COMPONENTS;
+        if stride == 0 || !words.is_multiple_of(stride) {
+            return Err(());
+        }
+
+        self.tx_setup(words / stride)?;
+        <Pluto<'a> as types::traits::Ctx>::tx_buffer_write(self, words / stride, data)
     }
 
     fn tx_buffer_push(&mut self, data: Vec<u8>) -> Result<(), ()> {
@@ -1127,23 +1844,88 @@ impl<'a> traits::RxTxDef<'a> for Pluto<'a> {
 }
 
 impl<'a> traits::AD9364<'a> for Pluto<'a> {
-    fn get_filter(&self) -> Vec<i32> {
-        todo!()
+    // The driver doesn't expose the taps, only a summary of the loaded filter. This returns the
+    // filter last loaded through this handle, None if there is none, and fails if the summary
+    // shows it has since been replaced (e.g. by another process).
+    fn get_cached_filter(&mut self) -> Result<Option<fir::FirFilter>, ()> {
+        let Some(filter) = self.fir_filter.clone() else {
+            return Ok(None);
+        };
+        let status = traits::Attribute::get_iio_dev_attr_str(self, "filter_fir_config", None)?;
+        if filter.matches_status(status.as_str())? {
+            Ok(Some(filter))
+        } else {
+            Err(())
+        }
     }
 
-    fn set_filter(&self, _filename: &str) {
-        todo!()
+    fn set_filter(&mut self, filename: &str) -> Result<(), ()> {
+        let filter = fir::FirFilter::load(filename)?;
+        self.set_fir_filter(filter)
     }
 
-    fn get_loopback(&self) -> Vec<i32> {
-        todo!()
+    fn set_fir_filter(&mut self, filter: fir::FirFilter) -> Result<(), ()> {
+        filter.validate()?;
+
+        let fir_old = traits::Attribute::get_iio_attr_int(
+            self,
+            "out",
+            "voltage_filter_fir_en",
+            Some(false),
+            None,
+        )?;
+        if fir_old != 0 {
+            traits::Attribute::set_iio_attr_int(
+                self,
+                "out",
+                "voltage_filter_fir_en",
+                Some(false),
+                0,
+                None,
+            )?;
+        }
+
+        traits::Attribute::set_iio_dev_attr_str(
+            self,
+            "filter_fir_config",
+            filter.to_string().as_str(),
+            None,
+        )?;
+        self.fir_filter = Some(filter);
+
+        if fir_old != 0 {
+            traits::Attribute::set_iio_attr_int(
+                self,
+                "out",
+                "voltage_filter_fir_en",
+                Some(false),
+                1,
+                None,
+            )?;
+        }
+        Ok(())
     }
 
-    fn set_loopback(&self, _value: datatypes::Loopback) {
-        todo!()
+    fn get_loopback(&mut self) -> Result<datatypes::Loopback, ()> {
+        let loopback = traits::Attribute::get_iio_debug_attr_str(self, "loopback", None)?;
+        datatypes::Loopback::from_value(loopback.trim().parse::<u32>().map_err(|_| ())?)
+    }
+
+    fn set_loopback(&mut self, value: datatypes::Loopback) -> Result<(), ()> {
+        traits::Attribute::set_iio_debug_attr_str(
+            self,
+            "loopback",
+            value.get_value().to_string().as_str(),
+            None,
+        )?;
+        if self.get_loopback()? == value {
+            Ok(())
+        } else {
+            Err(())
+        }
     }
 
-    fn get_gain_control_mode_chan0(&'a self) -> Result<String, ()> {
+    fn get_gain_control_mode_chan0(&self) -> Result<String, ()> {
         traits::Attribute::get_iio_attr_str(
             self,
             "voltage0",
@@ -1153,7 +1935,7 @@ impl<'a> traits::AD9364<'a> for Pluto<'a> {
         )
     }
 
-    fn set_gain_control_mode_chan0(&'a self, value: &'a str) -> Result<(), ()> {
+    fn set_gain_control_mode_chan0(&self, value: &str) -> Result<(), ()> {
         traits::Attribute::set_iio_attr_str(
             self,
             "voltage0",
@@ -1196,173 +1978,698 @@ impl<'a> traits::AD9364<'a> for Pluto<'a> {
         )
     }
 
-    fn get_rx_rf_bandwidth(&self) -> Result<u32, ()> {
-        traits::Attribute::get_iio_attr_int(self, "voltage0", "rf_bandwidth", Some(false), None)
-            .map(|f| f as u32)
-    }
-
-    fn set_rx_rf_bandwidth(&self, value: u32) -> Result<(), ()> {
-        traits::Attribute::set_iio_attr_int(
+    fn get_gain_control_modes_available(
+        &self,
+    ) -> Result<Vec<datatypes::GainControlMode>, error::AdiError> {
+        let modes = traits::Attribute::get_iio_attr_str(
             self,
             "voltage0",
-            "rf_bandwidth",
+            "gain_control_mode_available",
             Some(false),
-            value,
             None,
-        )
+        )?;
+        Ok(modes
+            .split_whitespace()
+            .filter_map(|f| datatypes::GainControlMode::from_value(f).ok())
+            .collect())
     }
 
-    fn get_tx_rf_bandwidth(&self) -> Result<u32, ()> {
-        traits::Attribute::get_iio_attr_int(self, "voltage0", "rf_bandwidth", Some(true), None)
-            .map(|f| f as u32)
+    fn get_gain_control_mode(
+        &self,
+        channel: usize,
+    ) -> Result<datatypes::GainControlMode, error::AdiError> {
+        let mode = traits::Attribute::get_iio_attr_str(
+            self,
+            phy_channel_name(channel)?,
+            "gain_control_mode",
+            Some(false),
+            None,
+        )?;
+        Ok(datatypes::GainControlMode::from_value(mode.as_str())?)
     }
 
-    fn set_tx_rf_bandwidth(&self, value: u32) -> Result<(), ()> {
-        traits::Attribute::set_iio_attr_int(
+    fn set_gain_control_mode(
+        &self,
+        channel: usize,
+        mode: datatypes::GainControlMode,
+    ) -> Result<(), error::AdiError> {
+        let channel_name = phy_channel_name(channel)?;
+        let available = self.get_gain_control_modes_available()?;
+        if !available.contains(&mode) {
+            return Err(error::AdiError::RangeError {
+                value: mode.get_value().to_owned(),
+                available: available
+                    .iter()
+                    .map(|f| f.get_value())
+                    .collect::<Vec<&str>>()
+                    .join(" "),
+            });
+        }
+        traits::Attribute::set_iio_attr_str(
             self,
-            "voltage0",
-            "rf_bandwidth",
-            Some(true),
-            value,
+            channel_name,
+            "gain_control_mode",
+            Some(false),
+            mode.get_value(),
             None,
-        )
+        )?;
+        Ok(())
     }
 
-    fn get_sample_rate(&self) -> Result<u32, ()> {
-        traits::Attribute::get_iio_attr_int(
+    fn get_rx_hardwaregain_available(
+        &self,
+        channel: usize,
+    ) -> Result<datatypes::AttrRange, error::AdiError> {
+        let available = traits::Attribute::get_iio_attr_str(
             self,
-            "voltage0",
-            "sampling_frequency",
+            phy_channel_name(channel)?,
+            "hardwaregain_available",
             Some(false),
             None,
-        )
-        .map(|f| f as u32)
+        )?;
+        Ok(datatypes::AttrRange::parse(available.as_str())?)
     }
 
-    fn set_sample_rate(&mut self, value: u32) -> Result<(), ()> {
-        static CURRENT_SAMPLING_THRESH: u32 = 2083333;
-        if value < 521_000 {
-            return Err(());
-        }
-        let (dec, fir) = match value {
-            x if x <= 20_000_000 => (4, fir::FIR_1.as_slice()),
-            x if x <= 40_000_000 => (2, fir::FIR_2.as_slice()),
-            x if x <= 53_333_333 => (2, fir::FIR_3.as_slice()),
-            _ => (2, fir::FIR_4.as_slice()),
-        };
-
-        let current_sampling_freq = self.get_sample_rate()?;
-        let fir_old = traits::Attribute::get_iio_attr_str(
+    fn get_rx_hardwaregain(&self, channel: usize) -> Result<f64, error::AdiError> {
+        let gain = traits::Attribute::get_iio_attr_str(
             self,
-            "out",
-            "voltage_filter_fir_en",
+            phy_channel_name(channel)?,
+            "hardwaregain",
             Some(false),
             None,
         )?;
-        let fir_number = fir_old.parse::<u32>().map_err(|_| ())?;
-
-        if fir_number != 0 {
-            if current_sampling_freq <= CURRENT_SAMPLING_THRESH {
-                let sampling_frequency_fixed = 3_000_000;
-                traits::Attribute::set_iio_attr_int(
-                    self,
-                    "voltage0",
-                    "sampling_frequency",
-                    Some(false),
-                    sampling_frequency_fixed,
-                    None,
-                )?;
-            }
-            let fir0 = 0;
-            traits::Attribute::set_iio_attr_int(
-                self,
-                "out",
-                "voltage_filter_fir_en",
-                Some(false),
-                fir0,
-                None,
-            )?;
-        }
+        Ok(parse_gain(gain.as_str())?)
+    }
 
-        let mut fir_config_str = format!("RX 3 GAIN -6 DEC {}\nTX 3 GAIN 0 INT {}\n", dec, dec);
-        for attr in fir {
-            fir_config_str = format!("{}{},{}\n", fir_config_str, attr, attr);
+    fn set_rx_hardwaregain(&self, channel: usize, value: f64) -> Result<(), error::AdiError> {
+        let channel_name = phy_channel_name(channel)?;
+        let available = self.get_rx_hardwaregain_available(channel)?;
+        if !available.contains(value) {
+            return Err(error::AdiError::RangeError {
+                value: value.to_string(),
+                available: available.to_string(),
+            });
         }
-        fir_config_str = format!("{}\n", fir_config_str);
-        traits::Attribute::set_iio_dev_attr_str(
+        traits::Attribute::set_iio_attr_float(
             self,
-            "filter_fir_config",
-            fir_config_str.as_str(),
+            channel_name,
+            "hardwaregain",
+            Some(false),
+            value,
             None,
         )?;
+        Ok(())
+    }
 
-        if value <= CURRENT_SAMPLING_THRESH {
-            let readbuf = traits::Attribute::get_iio_dev_attr_str(self, "tx_path_rates", None)?;
-            let dacrate = readbuf.split(" ").collect::<Vec<&str>>()[1]
-                .split(":")
-                .collect::<Vec<&str>>()[1];
-            let txrate = readbuf.split(" ").collect::<Vec<&str>>()[5]
-                .split(":")
-                .collect::<Vec<&str>>()[1];
-
-            let max_rate = ((dacrate.parse::<u32>().map_err(|_| ())?
-                / txrate.parse::<u32>().map_err(|_| ())?)
-                * 16) as usize;
-            if max_rate < fir.len() {
-                let sampling_frequency_fixed = 3_000_000;
-                traits::Attribute::set_iio_attr_int(
-                    self,
-                    "voltage0",
-                    "sampling_frequency",
-                    Some(false),
-                    sampling_frequency_fixed,
-                    None,
-                )?;
+    fn get_tx_hardwaregain_available(
+        &self,
+        channel: usize,
+    ) -> Result<datatypes::AttrRange, error::AdiError> {
+        let available = traits::Attribute::get_iio_attr_str(
+            self,
+            phy_channel_name(channel)?,
+            "hardwaregain_available",
+            Some(true),
+            None,
+        )?;
+        Ok(datatypes::AttrRange::parse(available.as_str())?)
+    }
+
+    fn get_tx_hardwaregain(&self, channel: usize) -> Result<f64, error::AdiError> {
+        let gain = traits::Attribute::get_iio_attr_str(
+            self,
+            phy_channel_name(channel)?,
+            "hardwaregain",
+            Some(true),
+            None,
+        )?;
+        Ok(parse_gain(gain.as_str())?)
+    }
+
+    fn set_tx_hardwaregain(&self, channel: usize, value: f64) -> Result<(), error::AdiError> {
+        let channel_name = phy_channel_name(channel)?;
+        let available = self.get_tx_hardwaregain_available(channel)?;
+        if !available.contains(value) {
+            return Err(error::AdiError::RangeError {
+                value: value.to_string(),
+                available: available.to_string(),
+            });
+        }
+        traits::Attribute::set_iio_attr_float(
+            self,
+            channel_name,
+            "hardwaregain",
+            Some(true),
+            value,
+            None,
+        )?;
+        Ok(())
+    }
+
+    fn get_rssi(&self, channel: usize) -> Result<f64, error::AdiError> {
+        let rssi = traits::Attribute::get_iio_attr_str(
+            self,
+            phy_channel_name(channel)?,
+            "rssi",
+            Some(false),
+            None,
+        )?;
+        Ok(parse_gain(rssi.as_str())?)
+    }
+
+    fn get_temperature(&self) -> Result<f64, error::AdiError> {
+        // in_temp0_input is reported in millidegrees Celsius
+        let temperature =
+            traits::Attribute::get_iio_attr_str(self, "temp0", "input", Some(false), None)?;
+        let millidegrees = temperature.trim().parse::<f64>().map_err(|_| ())?;
+        Ok(millidegrees / 1000.0)
+    }
+
+    fn get_quadrature_tracking(&self) -> Result<bool, error::AdiError> {
+        let enabled = traits::Attribute::get_iio_attr_int(
+            self,
+            "voltage0",
+            "quadrature_tracking_en",
+            Some(false),
+            None,
+        )?;
+        Ok(enabled != 0)
+    }
+
+    fn set_quadrature_tracking(&self, value: bool) -> Result<(), error::AdiError> {
+        traits::Attribute::set_iio_attr_int(
+            self,
+            "voltage0",
+            "quadrature_tracking_en",
+            Some(false),
+            value as i32,
+            None,
+        )?;
+        Ok(())
+    }
+
+    fn get_rf_dc_offset_tracking(&self) -> Result<bool, error::AdiError> {
+        let enabled = traits::Attribute::get_iio_attr_int(
+            self,
+            "voltage0",
+            "rf_dc_offset_tracking_en",
+            Some(false),
+            None,
+        )?;
+        Ok(enabled != 0)
+    }
+
+    fn set_rf_dc_offset_tracking(&self, value: bool) -> Result<(), error::AdiError> {
+        traits::Attribute::set_iio_attr_int(
+            self,
+            "voltage0",
+            "rf_dc_offset_tracking_en",
+            Some(false),
+            value as i32,
+            None,
+        )?;
+        Ok(())
+    }
+
+    fn get_bb_dc_offset_tracking(&self) -> Result<bool, error::AdiError> {
+        let enabled = traits::Attribute::get_iio_attr_int(
+            self,
+            "voltage0",
+            "bb_dc_offset_tracking_en",
+            Some(false),
+            None,
+        )?;
+        Ok(enabled != 0)
+    }
+
+    fn set_bb_dc_offset_tracking(&self, value: bool) -> Result<(), error::AdiError> {
+        traits::Attribute::set_iio_attr_int(
+            self,
+            "voltage0",
+            "bb_dc_offset_tracking_en",
+            Some(false),
+            value as i32,
+            None,
+        )?;
+        Ok(())
+    }
+
+    fn get_calibration_modes_available(
+        &mut self,
+    ) -> Result<Vec<datatypes::CalibrationMode>, error::AdiError> {
+        let modes = traits::Attribute::get_iio_dev_attr_str(self, "calib_mode_available", None)?;
+        Ok(modes
+            .split_whitespace()
+            .filter_map(|f| datatypes::CalibrationMode::from_value(f).ok())
+            .collect())
+    }
+
+    fn get_calibration_mode(&mut self) -> Result<datatypes::CalibrationMode, error::AdiError> {
+        let mode = traits::Attribute::get_iio_dev_attr_str(self, "calib_mode", None)?;
+        Ok(datatypes::CalibrationMode::from_value(mode.as_str())?)
+    }
+
+    fn set_calibration_mode(
+        &mut self,
+        mode: datatypes::CalibrationMode,
+    ) -> Result<(), error::AdiError> {
+        let available = self.get_calibration_modes_available()?;
+        if !available.contains(&mode) {
+            return Err(error::AdiError::RangeError {
+                value: mode.get_value().to_owned(),
+                available: available
+                    .iter()
+                    .map(|f| f.get_value())
+                    .collect::<Vec<&str>>()
+                    .join(" "),
+            });
+        }
+        traits::Attribute::set_iio_dev_attr_str(self, "calib_mode", mode.get_value(), None)?;
+        Ok(())
+    }
+
+    fn run_calibration(
+        &mut self,
+        mode: datatypes::CalibrationMode,
+        timeout: std::time::Duration,
+    ) -> Result<std::time::Duration, error::AdiError> {
+        let field = mode
+            .get_calibration_field()
+            .ok_or(error::AdiError::RangeError {
+                value: mode.get_value().to_owned(),
+                available: "manual_tx_quad tx_quad rf_dc_offs rssi_gain_step".to_owned(),
+            })?;
+        let start = std::time::Instant::now();
+        // The driver runs the calibration from the attribute write, poll the
+        // self-clearing start bit to make sure the sequencer is done
+        self.set_calibration_mode(mode)?;
+        while traits::Registers::read_register_field(self, "calibration_ctrl", field)? != 0 {
+            if start.elapsed() > timeout {
+                return Err(error::AdiError::TimeoutError);
             }
-            traits::Attribute::set_iio_attr_int(
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        Ok(start.elapsed())
+    }
+
+    fn get_ensm_modes_available(&mut self) -> Result<Vec<datatypes::EnsmMode>, error::AdiError> {
+        let modes = traits::Attribute::get_iio_dev_attr_str(self, "ensm_mode_available", None)?;
+        Ok(modes
+            .split_whitespace()
+            .filter_map(|f| datatypes::EnsmMode::from_value(f).ok())
+            .collect())
+    }
+
+    fn get_ensm_mode(&mut self) -> Result<datatypes::EnsmMode, error::AdiError> {
+        let mode = traits::Attribute::get_iio_dev_attr_str(self, "ensm_mode", None)?;
+        Ok(datatypes::EnsmMode::from_value(mode.as_str())?)
+    }
+
+    fn set_ensm_mode(&mut self, mode: datatypes::EnsmMode) -> Result<(), error::AdiError> {
+        let available = self.get_ensm_modes_available()?;
+        if !available.contains(&mode) {
+            return Err(error::AdiError::RangeError {
+                value: mode.get_value().to_owned(),
+                available: available
+                    .iter()
+                    .map(|f| f.get_value())
+                    .collect::<Vec<&str>>()
+                    .join(" "),
+            });
+        }
+        let current = self.get_ensm_mode()?;
+        if !current.can_transition_to(mode) {
+            traits::Attribute::set_iio_dev_attr_str(
                 self,
-                "out",
-                "voltage_filter_fir_en",
-                Some(false),
-                1,
+                "ensm_mode",
+                datatypes::EnsmMode::Alert.get_value(),
                 None,
             )?;
-            traits::Attribute::set_iio_attr_int(
+        }
+        traits::Attribute::set_iio_dev_attr_str(self, "ensm_mode", mode.get_value(), None)?;
+        if mode.matches_state(self.get_ensm_mode()?) {
+            Ok(())
+        } else {
+            Err(error::AdiError::IioError)
+        }
+    }
+
+    fn get_fastlock_profiles(&self) -> &fastlock::FastlockStore {
+        &self.fastlock
+    }
+
+    fn fastlock_store(
+        &mut self,
+        synthesizer: datatypes::Synthesizer,
+        index: u8,
+    ) -> Result<fastlock::FastlockProfile, ()> {
+        if index >= fastlock::FASTLOCK_PROFILES {
+            return Err(());
+        }
+        let channel_name = synthesizer.get_channel_name();
+        let index_string = index.to_string();
+        let frequency =
+            traits::Attribute::get_iio_attr_str(self, channel_name, "frequency", Some(true), None)?
+                .parse::<u64>()
+                .map_err(|_| ())?;
+        // Store the current synthesizer state, then select the profile to read back
+        traits::Attribute::set_iio_attr_str(
+            self,
+            channel_name,
+            "fastlock_store",
+            Some(true),
+            index_string.as_str(),
+            None,
+        )?;
+        traits::Attribute::set_iio_attr_str(
+            self,
+            channel_name,
+            "fastlock_save",
+            Some(true),
+            index_string.as_str(),
+            None,
+        )?;
+        let value = traits::Attribute::get_iio_attr_str(
+            self,
+            channel_name,
+            "fastlock_save",
+            Some(true),
+            None,
+        )?;
+        let profile =
+            fastlock::FastlockProfile::parse_save(synthesizer, frequency, value.as_str())?;
+        if profile.get_index() != index {
+            return Err(());
+        }
+        self.fastlock.insert(profile.clone());
+        Ok(profile)
+    }
+
+    fn fastlock_recall(
+        &mut self,
+        synthesizer: datatypes::Synthesizer,
+        index: u8,
+    ) -> Result<(), ()> {
+        if index >= fastlock::FASTLOCK_PROFILES {
+            return Err(());
+        }
+        traits::Attribute::set_iio_attr_str(
+            self,
+            synthesizer.get_channel_name(),
+            "fastlock_recall",
+            Some(true),
+            index.to_string().as_str(),
+            None,
+        )
+    }
+
+    fn fastlock_load(&mut self, profile: fastlock::FastlockProfile) -> Result<(), ()> {
+        traits::Attribute::set_iio_attr_str(
+            self,
+            profile.get_synthesizer().get_channel_name(),
+            "fastlock_load",
+            Some(true),
+            profile.get_load_value().as_str(),
+            None,
+        )?;
+        self.fastlock.insert(profile);
+        Ok(())
+    }
+
+    fn save_fastlock_profiles(&self, filename: &str) -> Result<(), ()> {
+        self.fastlock.save(filename)
+    }
+
+    fn load_fastlock_profiles(&mut self, filename: &str) -> Result<(), ()> {
+        let store = fastlock::FastlockStore::load(filename)?;
+        for profile in store.get_profiles() {
+            self.fastlock_load(profile.clone())?;
+        }
+        Ok(())
+    }
+
+    fn get_rx_rf_ports_wired(&self) -> &'static [datatypes::RxPort] {
+        &PLUTO_RX_PORTS
+    }
+
+    fn get_tx_rf_ports_wired(&self) -> &'static [datatypes::TxPort] {
+        &PLUTO_TX_PORTS
+    }
+
+    fn get_rx_rf_ports_available(&self) -> Result<Vec<datatypes::RxPort>, error::AdiError> {
+        let ports = traits::Attribute::get_iio_attr_str(
+            self,
+            "voltage0",
+            "rf_port_select_available",
+            Some(false),
+            None,
+        )?;
+        Ok(ports
+            .split_whitespace()
+            .filter_map(|f| datatypes::RxPort::from_value(f).ok())
+            .collect())
+    }
+
+    fn get_rx_rf_port(&self) -> Result<datatypes::RxPort, error::AdiError> {
+        let port = traits::Attribute::get_iio_attr_str(
+            self,
+            "voltage0",
+            "rf_port_select",
+            Some(false),
+            None,
+        )?;
+        Ok(datatypes::RxPort::from_value(port.as_str())?)
+    }
+
+    fn set_rx_rf_port(&self, port: datatypes::RxPort) -> Result<(), error::AdiError> {
+        let available = self.get_rx_rf_ports_available()?;
+        if !available.contains(&port) {
+            return Err(error::AdiError::RangeError {
+                value: port.get_value().to_owned(),
+                available: available
+                    .iter()
+                    .map(|f| f.get_value())
+                    .collect::<Vec<&str>>()
+                    .join(" "),
+            });
+        }
+        traits::Attribute::set_iio_attr_str(
+            self,
+            "voltage0",
+            "rf_port_select",
+            Some(false),
+            port.get_value(),
+            None,
+        )?;
+        Ok(())
+    }
+
+    fn get_tx_rf_ports_available(&self) -> Result<Vec<datatypes::TxPort>, error::AdiError> {
+        let ports = traits::Attribute::get_iio_attr_str(
+            self,
+            "voltage0",
+            "rf_port_select_available",
+            Some(true),
+            None,
+        )?;
+        Ok(ports
+            .split_whitespace()
+            .filter_map(|f| datatypes::TxPort::from_value(f).ok())
+            .collect())
+    }
+
+    fn get_tx_rf_port(&self) -> Result<datatypes::TxPort, error::AdiError> {
+        let port = traits::Attribute::get_iio_attr_str(
+            self,
+            "voltage0",
+            "rf_port_select",
+            Some(true),
+            None,
+        )?;
+        Ok(datatypes::TxPort::from_value(port.as_str())?)
+    }
+
+    fn set_tx_rf_port(&self, port: datatypes::TxPort) -> Result<(), error::AdiError> {
+        let available = self.get_tx_rf_ports_available()?;
+        if !available.contains(&port) {
+            return Err(error::AdiError::RangeError {
+                value: port.get_value().to_owned(),
+                available: available
+                    .iter()
+                    .map(|f| f.get_value())
+                    .collect::<Vec<&str>>()
+                    .join(" "),
+            });
+        }
+        traits::Attribute::set_iio_attr_str(
+            self,
+            "voltage0",
+            "rf_port_select",
+            Some(true),
+            port.get_value(),
+            None,
+        )?;
+        Ok(())
+    }
+
+    fn set_rf_ports_for_lo(
+        &self,
+    ) -> Result<(datatypes::RxPort, datatypes::TxPort), error::AdiError> {
+        let rx_lo = self.get_rx_lo()?;
+        let rx_available = self
+            .get_rx_rf_ports_available()?
+            .into_iter()
+            .filter(|f| self.get_rx_rf_ports_wired().contains(f))
+            .collect::<Vec<datatypes::RxPort>>();
+        let rx_port = datatypes::RxPort::for_frequency(rx_lo, rx_available.as_slice())
+            .ok_or(error::AdiError::NoRfPortError(rx_lo))?;
+        let tx_lo = self.get_tx_lo()?;
+        let tx_available = self
+            .get_tx_rf_ports_available()?
+            .into_iter()
+            .filter(|f| self.get_tx_rf_ports_wired().contains(f))
+            .collect::<Vec<datatypes::TxPort>>();
+        let tx_port = datatypes::TxPort::for_frequency(tx_lo, tx_available.as_slice())
+            .ok_or(error::AdiError::NoRfPortError(tx_lo))?;
+        self.set_rx_rf_port(rx_port)?;
+        self.set_tx_rf_port(tx_port)?;
+        Ok((rx_port, tx_port))
+    }
+
+    fn get_rx_rf_bandwidth(&self) -> Result<u32, error::AdiError> {
+        let value = traits::Attribute::get_iio_attr_int(
+            self,
+            "voltage0",
+            "rf_bandwidth",
+            Some(false),
+            None,
+        )?;
+        Ok(value as u32)
+    }
+
+    fn set_rx_rf_bandwidth(&self, value: u32) -> Result<(), error::AdiError> {
+        self.ranges.rx_rf_bandwidth.check(value as f64)?;
+        traits::Attribute::set_iio_attr_int(
+            self,
+            "voltage0",
+            "rf_bandwidth",
+            Some(false),
+            value,
+            None,
+        )?;
+        Ok(())
+    }
+
+    fn get_tx_rf_bandwidth(&self) -> Result<u32, error::AdiError> {
+        let value = traits::Attribute::get_iio_attr_int(
+            self,
+            "voltage0",
+            "rf_bandwidth",
+            Some(true),
+            None,
+        )?;
+        Ok(value as u32)
+    }
+
+    fn set_tx_rf_bandwidth(&self, value: u32) -> Result<(), error::AdiError> {
+        self.ranges.tx_rf_bandwidth.check(value as f64)?;
+        traits::Attribute::set_iio_attr_int(
+            self,
+            "voltage0",
+            "rf_bandwidth",
+            Some(true),
+            value,
+            None,
+        )?;
+        Ok(())
+    }
+
+    // The RX core reports its rate after the FPGA decimation
+    fn get_sample_rate(&self) -> Result<u32, error::AdiError> {
+        let rate = match self.rxadc.as_ref() {
+            Some(rxadc) => traits::Attribute::get_iio_attr_int(
                 self,
                 "voltage0",
                 "sampling_frequency",
                 Some(false),
-                value,
-                None,
-            )?;
-        } else {
+                Some(rxadc),
+            )? as u32,
+            None => self.get_phy_sample_rate()?,
+        };
+        Ok(rate)
+    }
+
+    fn set_sample_rate(&mut self, value: u32) -> Result<(), error::AdiError> {
+        let (phy_rate, fpga_filter) = self.get_phy_rate(value)?;
+        let (transaction, filter) = self.get_phy_sample_rate_transaction(phy_rate)?;
+        let transaction = self.get_fpga_filter_transaction(transaction, fpga_filter)?;
+        traits::Attribute::apply_iio_attr_transaction(self, &transaction)?;
+        self.fir_filter = Some(filter);
+        Ok(())
+    }
+
+    fn set_sample_rate_designed(&mut self, design: fir::FirDesign) -> Result<(), ()> {
+        let filter = design.design()?;
+        let rx_path_rates = filter.rx_path_rates.ok_or(())?;
+        self.set_fir_filter(filter)?;
+        traits::Attribute::set_iio_attr_int(
+            self,
+            "out",
+            "voltage_filter_fir_en",
+            Some(false),
+            1,
+            None,
+        )?;
+        // The driver applies the RX/TX path rates carried by the filter, a driver which doesn't
+        // picks its own clock chain from the sample rate
+        if traits::AD9364::get_rx_path_rates(self)? != rx_path_rates {
             traits::Attribute::set_iio_attr_int(
                 self,
                 "voltage0",
                 "sampling_frequency",
                 Some(false),
-                value,
-                None,
-            )?;
-            traits::Attribute::set_iio_attr_int(
-                self,
-                "out",
-                "voltage_filter_fir_en",
-                Some(false),
-                1,
+                design.sample_rate,
                 None,
             )?;
         }
+        // The taps were designed for the FIR rate, any other clock chain would move the band edges
+        let rates = traits::AD9364::get_rx_path_rates(self)?;
+        if rates.sample != rx_path_rates.sample || rates.rf != rx_path_rates.rf {
+            return Err(());
+        }
+        self.set_rx_rf_bandwidth(design.rf_bandwidth)
+            .map_err(|_| ())?;
+        self.set_tx_rf_bandwidth(design.rf_bandwidth)
+            .map_err(|_| ())
+    }
 
-        Ok(())
+    fn get_rx_path_rates(&mut self) -> Result<datatypes::PathRates, ()> {
+        let rates = traits::Attribute::get_iio_dev_attr_str(self, "rx_path_rates", None)?;
+        datatypes::PathRates::parse(rates.as_str(), false)
     }
 
-    fn get_rx_lo(&self) -> Result<u64, ()> {
-        traits::Attribute::get_iio_attr_int(self, "altvoltage0", "frequency", Some(true), None)
-            .map(|f| f as u64)
+    fn get_tx_path_rates(&mut self) -> Result<datatypes::PathRates, ()> {
+        let rates = traits::Attribute::get_iio_dev_attr_str(self, "tx_path_rates", None)?;
+        datatypes::PathRates::parse(rates.as_str(), true)
     }
 
-    fn set_rx_lo(&self, value: u64) -> Result<(), ()> {
+    fn get_rx_lo(&self) -> Result<u64, error::AdiError> {
+        let frequency = traits::Attribute::get_iio_attr_int(
+            self,
+            "altvoltage0",
+            "frequency",
+            Some(true),
+            None,
+        )?;
+        Ok(frequency as u64)
+    }
+
+    fn set_rx_lo(&self, value: u64) -> Result<(), error::AdiError> {
+        self.ranges.rx_lo.check(value as f64)?;
+        // A powered-down or externally driven LO cannot be tuned from here
+        if !self.is_lo_tunable(datatypes::Synthesizer::Rx) {
+            return Err(error::AdiError::LoNotTunableError);
+        }
         traits::Attribute::set_iio_attr_int(
             self,
             "altvoltage0",
@@ -1370,15 +2677,27 @@ impl<'a> traits::AD9364<'a> for Pluto<'a> {
             Some(true),
             value,
             None,
-        )
+        )?;
+        Ok(())
     }
 
-    fn get_tx_lo(&self) -> Result<u64, ()> {
-        traits::Attribute::get_iio_attr_int(self, "altvoltage1", "frequency", Some(true), None)
-            .map(|f| f as u64)
+    fn get_tx_lo(&self) -> Result<u64, error::AdiError> {
+        let frequency = traits::Attribute::get_iio_attr_int(
+            self,
+            "altvoltage1",
+            "frequency",
+            Some(true),
+            None,
+        )?;
+        Ok(frequency as u64)
     }
 
-    fn set_tx_lo(&self, value: u64) -> Result<(), ()> {
+    fn set_tx_lo(&self, value: u64) -> Result<(), error::AdiError> {
+        self.ranges.tx_lo.check(value as f64)?;
+        // A powered-down or externally driven LO cannot be tuned from here
+        if !self.is_lo_tunable(datatypes::Synthesizer::Tx) {
+            return Err(error::AdiError::LoNotTunableError);
+        }
         traits::Attribute::set_iio_attr_int(
             self,
             "altvoltage1",
@@ -1386,7 +2705,109 @@ impl<'a> traits::AD9364<'a> for Pluto<'a> {
             Some(true),
             value,
             None,
-        )
+        )?;
+        Ok(())
+    }
+
+    fn get_radio_ranges(&self) -> &datatypes::RadioRanges {
+        &self.ranges
+    }
+
+    fn get_lo_external(
+        &self,
+        synthesizer: datatypes::Synthesizer,
+    ) -> Result<bool, error::AdiError> {
+        let external = traits::Attribute::get_iio_attr_str(
+            self,
+            synthesizer.get_channel_name(),
+            "external",
+            Some(true),
+            None,
+        )?;
+        Ok(external.parse::<u32>().map_err(|_| ())? != 0)
+    }
+
+    fn set_lo_external(
+        &mut self,
+        synthesizer: datatypes::Synthesizer,
+        value: bool,
+    ) -> Result<(), error::AdiError> {
+        traits::Attribute::set_iio_attr_str(
+            self,
+            synthesizer.get_channel_name(),
+            "external",
+            Some(true),
+            if value { "1" } else { "0" },
+            None,
+        )?;
+        self.lo_external[synthesizer.get_index()] = value;
+        Ok(())
+    }
+
+    fn get_lo_powerdown(
+        &self,
+        synthesizer: datatypes::Synthesizer,
+    ) -> Result<bool, error::AdiError> {
+        let powerdown = traits::Attribute::get_iio_attr_str(
+            self,
+            synthesizer.get_channel_name(),
+            "powerdown",
+            Some(true),
+            None,
+        )?;
+        Ok(powerdown.parse::<u32>().map_err(|_| ())? != 0)
+    }
+
+    fn set_lo_powerdown(
+        &mut self,
+        synthesizer: datatypes::Synthesizer,
+        value: bool,
+    ) -> Result<(), error::AdiError> {
+        traits::Attribute::set_iio_attr_str(
+            self,
+            synthesizer.get_channel_name(),
+            "powerdown",
+            Some(true),
+            if value { "1" } else { "0" },
+            None,
+        )?;
+        self.lo_powerdown[synthesizer.get_index()] = value;
+        Ok(())
+    }
+
+    fn get_hw_serial(&self) -> Option<String> {
+        self.context
+            .get_attrs()
+            .get("hw_serial")
+            .map(|f| f.to_string())
+    }
+
+    fn get_xo_correction_available(&mut self) -> Result<datatypes::AttrRange, error::AdiError> {
+        let available =
+            traits::Attribute::get_iio_dev_attr_str(self, "xo_correction_available", None)?;
+        Ok(datatypes::AttrRange::parse(available.as_str())?)
+    }
+
+    fn get_xo_correction(&mut self) -> Result<u64, error::AdiError> {
+        let xo_correction = traits::Attribute::get_iio_dev_attr_str(self, "xo_correction", None)?;
+        Ok(xo_correction.trim().parse::<u64>().map_err(|_| ())?)
+    }
+
+    fn set_xo_correction(&mut self, value: u64) -> Result<(), error::AdiError> {
+        let available = self.get_xo_correction_available()?;
+        if !available.contains(value as f64) {
+            return Err(error::AdiError::RangeError {
+                value: value.to_string(),
+                available: available.to_string(),
+            });
+        }
+        traits::Attribute::set_iio_dev_attr_str(
+            self,
+            "xo_correction",
+            value.to_string().as_str(),
+            None,
+        )?;
+        Ok(())
     }
 
     fn init() -> traits::Ad9364InitResult {
@@ -1420,49 +2841,22 @@ impl<'a> traits::DecIntFPGAFilter for Pluto<'a> {
             Some(output),
             Some(dev),
         )?;
-        let rates_replaced = sfa.trim().replace("[", "").replace("]", "");
-        let rates = rates_replaced.split(" ");
-        let mut my_vec = vec![];
-        for rate in rates {
-            my_vec.push(rate.parse::<u32>().map_err(|_| ())?);
+        match attribute::AttrAvailable::parse(sfa.as_str()) {
+            attribute::AttrAvailable::List(list) => list
+                .iter()
+                .map(|f| u32::try_from(attribute::parse_int(f)?).map_err(|_| ()))
+                .collect(),
+            attribute::AttrAvailable::Range(_) => Err(()),
         }
-        Ok(my_vec)
     }
 
     fn get_rx_dec8_filter_en(&self) -> Result<bool, ()> {
-        let rxadc = self.rxadc.as_ref().ok_or(())?;
-        let rates = self.get_rates(rxadc, false)?;
-        let sf_string = traits::Attribute::get_iio_attr_str(
-            self,
-            "voltage0",
-            "sampling_frequency",
-            Some(false),
-            Some(rxadc),
-        )?;
-        let bytes_with_dot = sf_string
-            .as_bytes()
-            .iter()
-            .take_while(|f| f.is_ascii_digit())
-            .copied()
-            .collect::<Vec<u8>>();
-        let bytes_string = String::from_utf8_lossy(bytes_with_dot.as_slice()).to_string();
-        let sf = bytes_string.parse::<u32>().map_err(|_| ())?;
-        let mut min_rate = u32::MAX;
-        for rate in rates {
-            if rate < min_rate {
-                min_rate = rate;
-            }
-        }
-        Ok(min_rate == sf)
+        self.get_fpga_filter_en(false)
     }
 
     fn set_rx_dec8_filter_en(&self, value: bool) -> Result<(), ()> {
         let rxadc = self.rxadc.as_ref().ok_or(())?;
-        let rates = self.get_rates(rxadc, false)?;
-        if rates.len() < 2 {
-            return Err(());
-        }
-        let sr = if value { rates[1] } else { rates[0] };
+        let sr = self.get_fpga_filter_rate(false, value)?;
         traits::Attribute::set_iio_attr_str(
             self,
             "voltage0",
@@ -1474,39 +2868,12 @@ impl<'a> traits::DecIntFPGAFilter for Pluto<'a> {
     }
 
     fn get_tx_int8_filter_en(&self) -> Result<bool, ()> {
-        let txdac = self.txdac.as_ref().ok_or(())?;
-        let rates = self.get_rates(txdac, true)?;
-        let sf_string = traits::Attribute::get_iio_attr_str(
-            self,
-            "voltage0",
-            "sampling_frequency",
-            Some(true),
-            Some(txdac),
-        )?;
-        let bytes_with_dot = sf_string
-            .as_bytes()
-            .iter()
-            .take_while(|f| f.is_ascii_digit())
-            .copied()
-            .collect::<Vec<u8>>();
-        let bytes_string = String::from_utf8_lossy(bytes_with_dot.as_slice()).to_string();
-        let sf = bytes_string.parse::<u32>().map_err(|_| ())?;
-        let mut min_rate = u32::MAX;
-        for rate in rates {
-            if rate < min_rate {
-                min_rate = rate;
-            }
-        }
-        Ok(min_rate == sf)
+        self.get_fpga_filter_en(true)
     }
 
     fn set_tx_int8_filter_en(&self, value: bool) -> Result<(), ()> {
         let txdac = self.txdac.as_ref().ok_or(())?;
-        let rates = self.get_rates(txdac, false)?;
-        if rates.len() < 2 {
-            return Err(());
-        }
-        let sr = if value { rates[1] } else { rates[0] };
+        let sr = self.get_fpga_filter_rate(true, value)?;
         traits::Attribute::set_iio_attr_str(
             self,
             "voltage0",
@@ -1517,3 +2884,216 @@ impl<'a> traits::DecIntFPGAFilter for Pluto<'a> {
         )
     }
 }
+
+impl<'a> traits::Registers<'a> for Pluto<'a> {
+    fn reg_read(&mut self, address: u32) -> Result<u32, ()> {
+        let iio_device = self.context.find_device_mut(self.ctrl.get_name())?;
+        types::device_trigger::DeviceTrigger::reg_read(iio_device, address).map_err(|_| ())
+    }
+
+    fn reg_write(&mut self, address: u32, value: u32) -> Result<(), ()> {
+        let iio_device = self.context.find_device_mut(self.ctrl.get_name())?;
+        types::device_trigger::DeviceTrigger::reg_write(iio_device, address, value).map_err(|_| ())
+    }
+
+    fn read_register(&mut self, name: &str) -> Result<u8, ()> {
+        let register = registers::find_register(name).ok_or(())?;
+        Ok(self.reg_read(register.address as u32)? as u8)
+    }
+
+    fn write_register(&mut self, name: &str, value: u8) -> Result<(), ()> {
+        let register = registers::find_register(name).ok_or(())?;
+        if register.access == registers::RegisterAccess::ReadOnly {
+            return Err(());
+        }
+        self.reg_write(register.address as u32, value as u32)
+    }
+
+    fn read_register_field(&mut self, register: &str, field: &str) -> Result<u8, ()> {
+        let register = registers::find_register(register).ok_or(())?;
+        let field = register.find_field(field).ok_or(())?;
+        let value = self.reg_read(register.address as u32)? as u8;
+        Ok(field.extract(value))
+    }
+
+    fn write_register_field(&mut self, register: &str, field: &str, value: u8) -> Result<(), ()> {
+        let register = registers::find_register(register).ok_or(())?;
+        if register.access == registers::RegisterAccess::ReadOnly {
+            return Err(());
+        }
+        let field = register.find_field(field).ok_or(())?;
+        let current = self.reg_read(register.address as u32)? as u8;
+        let updated = field.insert(current, value)?;
+        if updated == current {
+            return Ok(());
+        }
+        self.reg_write(register.address as u32, updated as u32)?;
+        if self.reg_read(register.address as u32)? as u8 == updated {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    fn dump_registers(&mut self) -> Result<registers::RegisterDump, ()> {
+        let mut values = vec![];
+        for register in registers::AD9361_REGISTERS {
+            values.push((register, self.reg_read(register.address as u32)? as u8));
+        }
+        Ok(registers::RegisterDump::new(values))
+    }
+}
+
+impl<'a> traits::ConfigSnapshot<'a> for Pluto<'a> {
+    fn snapshot_config(&mut self) -> Result<config::RadioConfig, error::AdiError> {
+        let fir_enabled = traits::Attribute::get_iio_attr_int(
+            self,
+            "out",
+            "voltage_filter_fir_en",
+            Some(false),
+            None,
+        )? != 0;
+        // Only a filter loaded through this handle can be read back
+        let fir_filter = if fir_enabled {
+            self.get_cached_filter()
+                .ok()
+                .flatten()
+                .map(|f| f.to_string())
+        } else {
+            None
+        };
+        let mut skipped = vec![];
+        if fir_enabled && fir_filter.is_none() {
+            skipped.push("fir_filter".to_owned());
+        }
+
+        let mut channels = vec![];
+        for channel in 0..2 {
+            // AD9364 based radios only expose the first channel
+            let gain_control_mode = match self.get_gain_control_mode(channel) {
+                Ok(mode) => mode,
+                Err(_) if channel > 0 => break,
+                Err(e) => return Err(e),
+            };
+            channels.push(config::ChannelConfig {
+                gain_control_mode,
+                rx_hardwaregain: self.get_rx_hardwaregain(channel)?,
+                tx_hardwaregain: self.get_tx_hardwaregain(channel)?,
+            });
+        }
+
+        Ok(config::RadioConfig {
+            rx_lo: self.get_rx_lo()?,
+            tx_lo: self.get_tx_lo()?,
+            sample_rate: self.get_sample_rate()?,
+            rx_rf_bandwidth: self.get_rx_rf_bandwidth()?,
+            tx_rf_bandwidth: self.get_tx_rf_bandwidth()?,
+            rx_port: self.get_rx_rf_port()?,
+            tx_port: self.get_tx_rf_port()?,
+            rx_enabled_channels: self.rx_enabled_channels.clone(),
+            tx_enabled_channels: self.tx_enabled_channels.clone(),
+            fir_filter,
+            calibration: config::CalibrationConfig {
+                quadrature_tracking: self.get_quadrature_tracking()?,
+                rf_dc_offset_tracking: self.get_rf_dc_offset_tracking()?,
+                bb_dc_offset_tracking: self.get_bb_dc_offset_tracking()?,
+            },
+            channels,
+            dds: config::DdsConfig {
+                frequencies: self.get_dds_frequencies()?,
+                scales: self.get_dds_scales()?,
+                phases: self.get_dds_phases()?,
+                enabled: self.get_dds_enabled()?,
+            },
+            skipped,
+        })
+    }
+
+    fn restore_config(&mut self, config: &config::RadioConfig) -> Result<(), error::AdiError> {
+        // Without a FIR in the snapshot set_sample_rate loads its own, refuse to silently
+        // replace an active filter this handle knows nothing about
+        if config.fir_filter.is_none() {
+            let fir_enabled = traits::Attribute::get_iio_attr_int(
+                self,
+                "out",
+                "voltage_filter_fir_en",
+                Some(false),
+                None,
+            )? != 0;
+            if fir_enabled && !matches!(self.get_cached_filter(), Ok(Some(_))) {
+                return Err(error::AdiError::VerificationError(vec![
+                    "fir_filter".to_owned(),
+                ]));
+            }
+        }
+
+        // The FIR goes first so the sample rate is applied through a clock chain
+        // the filter supports, set_sample_rate would replace it with its own
+        match &config.fir_filter {
+            Some(ftr) => {
+                self.set_fir_filter(fir::FirFilter::parse(ftr.as_str())?)?;
+                traits::Attribute::set_iio_attr_int(
+                    self,
+                    "out",
+                    "voltage_filter_fir_en",
+                    Some(false),
+                    1,
+                    None,
+                )?;
+                let (phy_rate, fpga_filter) = self.get_phy_rate(config.sample_rate)?;
+                traits::Attribute::set_iio_attr_int(
+                    self,
+                    "voltage0",
+                    "sampling_frequency",
+                    Some(false),
+                    phy_rate,
+                    None,
+                )?;
+                self.set_fpga_filters(fpga_filter)?;
+            }
+            None => self.set_sample_rate(config.sample_rate)?,
+        }
+        self.set_rx_rf_bandwidth(config.rx_rf_bandwidth)?;
+        self.set_tx_rf_bandwidth(config.tx_rf_bandwidth)?;
+
+        // Ports and tracking calibrations depend on the LO band
+        self.set_rx_lo(config.rx_lo)?;
+        self.set_tx_lo(config.tx_lo)?;
+        self.set_rx_rf_port(config.rx_port)?;
+        self.set_tx_rf_port(config.tx_port)?;
+        self.set_quadrature_tracking(config.calibration.quadrature_tracking)?;
+        self.set_rf_dc_offset_tracking(config.calibration.rf_dc_offset_tracking)?;
+        self.set_bb_dc_offset_tracking(config.calibration.bb_dc_offset_tracking)?;
+
+        // The RX gain can only be written once the channel is in manual mode
+        for (channel, channel_config) in config.channels.iter().enumerate() {
+            self.set_gain_control_mode(channel, channel_config.gain_control_mode)?;
+            if channel_config.gain_control_mode == datatypes::GainControlMode::Manual {
+                self.set_rx_hardwaregain(channel, channel_config.rx_hardwaregain)?;
+            }
+            self.set_tx_hardwaregain(channel, channel_config.tx_hardwaregain)?;
+        }
+
+        // Buffers are sized for the enabled channels, drop them so they get rebuilt
+        if self.rx_enabled_channels != config.rx_enabled_channels {
+            self.rx_destroy_buffer();
+            self.rx_enabled_channels = config.rx_enabled_channels.clone();
+        }
+        if self.tx_enabled_channels != config.tx_enabled_channels {
+            self.tx_destroy_buffer();
+            self.tx_enabled_channels = config.tx_enabled_channels.clone();
+        }
+
+        self.set_dds_frequencies(config.dds.frequencies.clone())?;
+        self.set_dds_scales(config.dds.scales.clone())?;
+        self.set_dds_phases(config.dds.phases.clone())?;
+        self.set_dds_enabled(config.dds.enabled.clone())?;
+
+        let diffs = config.diff(&self.snapshot_config()?);
+        if diffs.is_empty() {
+            Ok(())
+        } else {
+            Err(error::AdiError::VerificationError(diffs))
+        }
+    }
+}
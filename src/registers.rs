@@ -0,0 +1,378 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RegisterAccess {
+    ReadOnly,
+    ReadWrite,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegisterField {
+    pub name: &'static str,
+    pub shift: u8,
+    pub width: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Register {
+    pub name: &'static str,
+    pub address: u16,
+    pub access: RegisterAccess,
+    pub fields: &'static [RegisterField],
+}
+
+const fn field(name: &'static str, shift: u8, width: u8) -> RegisterField {
+    RegisterField { name, shift, width }
+}
+
+impl RegisterField {
+    pub fn get_mask(&self) -> u8 {
+        (((1u16 << self.width) - 1) << self.shift) as u8
+    }
+
+    pub fn extract(&self, register_value: u8) -> u8 {
+        (register_value & self.get_mask()) >> self.shift
+    }
+
+    pub fn insert(&self, register_value: u8, value: u8) -> Result<u8, ()> {
+        if (value as u16) >= (1u16 << self.width) {
+            return Err(());
+        }
+        Ok((register_value & !self.get_mask()) | (value << self.shift))
+    }
+}
+
+impl Register {
+    pub fn find_field(&self, name: &str) -> Option<&RegisterField> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+}
+
+// Subset of the AD9361 SPI register map (UG-671) useful when debugging configuration
+pub static AD9361_REGISTERS: &[Register] = &[
+    Register {
+        name: "tx_enable_filter_ctrl",
+        address: 0x002,
+        access: RegisterAccess::ReadWrite,
+        fields: &[
+            field("tx2_enable", 7, 1),
+            field("tx1_enable", 6, 1),
+            field("thb3_enable_interp", 4, 2),
+            field("thb2_enable", 3, 1),
+            field("thb1_enable", 2, 1),
+            field("tx_fir_enable_interp", 0, 2),
+        ],
+    },
+    Register {
+        name: "rx_enable_filter_ctrl",
+        address: 0x003,
+        access: RegisterAccess::ReadWrite,
+        fields: &[
+            field("rx2_enable", 7, 1),
+            field("rx1_enable", 6, 1),
+            field("rhb3_enable_decim", 4, 2),
+            field("rhb2_enable", 3, 1),
+            field("rhb1_enable", 2, 1),
+            field("rx_fir_enable_decim", 0, 2),
+        ],
+    },
+    Register {
+        name: "input_select",
+        address: 0x004,
+        access: RegisterAccess::ReadWrite,
+        fields: &[field("tx_output", 6, 1), field("rx_input", 0, 6)],
+    },
+    Register {
+        name: "rfpll_dividers",
+        address: 0x005,
+        access: RegisterAccess::ReadWrite,
+        fields: &[field("tx_vco_divider", 4, 4), field("rx_vco_divider", 0, 4)],
+    },
+    Register {
+        name: "bbpll",
+        address: 0x00A,
+        access: RegisterAccess::ReadWrite,
+        fields: &[field("bbpll_divider", 0, 3)],
+    },
+    Register {
+        name: "ensm_config_1",
+        address: 0x014,
+        access: RegisterAccess::ReadWrite,
+        fields: &[
+            field("enable_rx_data_port_for_cal", 7, 1),
+            field("force_rx_on", 6, 1),
+            field("force_tx_on", 5, 1),
+            field("enable_ensm_pin_ctrl", 4, 1),
+            field("level_mode", 3, 1),
+            field("force_alert_state", 2, 1),
+            field("auto_gain_lock", 1, 1),
+            field("to_alert", 0, 1),
+        ],
+    },
+    Register {
+        name: "ensm_config_2",
+        address: 0x015,
+        access: RegisterAccess::ReadWrite,
+        fields: &[
+            field("fdd_external_ctrl_enable", 7, 1),
+            field("power_down_rx_synth", 6, 1),
+            field("power_down_tx_synth", 5, 1),
+            field("txnrx_spi_ctrl", 4, 1),
+            field("synth_pin_ctrl_mode", 3, 1),
+            field("dual_synth_mode", 2, 1),
+            field("rx_synth_ready_mask", 1, 1),
+            field("tx_synth_ready_mask", 0, 1),
+        ],
+    },
+    Register {
+        name: "calibration_ctrl",
+        address: 0x016,
+        access: RegisterAccess::ReadWrite,
+        fields: &[
+            field("rx_bb_tune", 7, 1),
+            field("tx_bb_tune", 6, 1),
+            field("must_be_zero", 5, 1),
+            field("tx_quad_cal", 4, 1),
+            field("rx_gain_step_cal", 3, 1),
+            field("tx_cal", 2, 1),
+            field("dc_cal_rf_start", 1, 1),
+            field("dc_cal_bb_start", 0, 1),
+        ],
+    },
+    Register {
+        name: "state",
+        address: 0x017,
+        access: RegisterAccess::ReadOnly,
+        fields: &[field("calibration_state", 4, 4), field("ensm_state", 0, 4)],
+    },
+    Register {
+        name: "product_id",
+        address: 0x037,
+        access: RegisterAccess::ReadOnly,
+        fields: &[field("product_id", 3, 5), field("revision", 0, 3)],
+    },
+    Register {
+        name: "tx1_atten_0",
+        address: 0x073,
+        access: RegisterAccess::ReadWrite,
+        fields: &[field("tx1_atten", 0, 8)],
+    },
+    Register {
+        name: "tx1_atten_1",
+        address: 0x074,
+        access: RegisterAccess::ReadWrite,
+        fields: &[field("tx1_atten_msb", 0, 1)],
+    },
+    Register {
+        name: "tx2_atten_0",
+        address: 0x075,
+        access: RegisterAccess::ReadWrite,
+        fields: &[field("tx2_atten", 0, 8)],
+    },
+    Register {
+        name: "tx2_atten_1",
+        address: 0x076,
+        access: RegisterAccess::ReadWrite,
+        fields: &[field("tx2_atten_msb", 0, 1)],
+    },
+    Register {
+        name: "agc_config_1",
+        address: 0x0FA,
+        access: RegisterAccess::ReadWrite,
+        fields: &[
+            field("rx2_gain_ctrl_setup", 2, 2),
+            field("rx1_gain_ctrl_setup", 0, 2),
+        ],
+    },
+    Register {
+        name: "rx1_manual_gain",
+        address: 0x109,
+        access: RegisterAccess::ReadWrite,
+        fields: &[field("rx1_gain_index", 0, 7)],
+    },
+    Register {
+        name: "rx2_manual_gain",
+        address: 0x10C,
+        access: RegisterAccess::ReadWrite,
+        fields: &[field("rx2_gain_index", 0, 7)],
+    },
+    Register {
+        name: "rx_synth_integer_byte_0",
+        address: 0x231,
+        access: RegisterAccess::ReadWrite,
+        fields: &[field("rx_synth_integer_lsb", 0, 8)],
+    },
+    Register {
+        name: "rx_synth_integer_byte_1",
+        address: 0x232,
+        access: RegisterAccess::ReadWrite,
+        fields: &[field("rx_synth_integer_msb", 0, 3)],
+    },
+    Register {
+        name: "rx_synth_vco_lock",
+        address: 0x247,
+        access: RegisterAccess::ReadOnly,
+        fields: &[field("rx_synth_lock", 1, 1)],
+    },
+    Register {
+        name: "tx_synth_integer_byte_0",
+        address: 0x271,
+        access: RegisterAccess::ReadWrite,
+        fields: &[field("tx_synth_integer_lsb", 0, 8)],
+    },
+    Register {
+        name: "tx_synth_integer_byte_1",
+        address: 0x272,
+        access: RegisterAccess::ReadWrite,
+        fields: &[field("tx_synth_integer_msb", 0, 3)],
+    },
+    Register {
+        name: "tx_synth_vco_lock",
+        address: 0x287,
+        access: RegisterAccess::ReadOnly,
+        fields: &[field("tx_synth_lock", 1, 1)],
+    },
+];
+
+pub fn find_register(name: &str) -> Option<&'static Register> {
+    AD9361_REGISTERS.iter().find(|f| f.name == name)
+}
+
+pub fn find_register_by_address(address: u16) -> Option<&'static Register> {
+    AD9361_REGISTERS.iter().find(|f| f.address == address)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegisterDump {
+    values: Vec<(&'static Register, u8)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegisterDiff {
+    pub register: &'static str,
+    pub field: &'static str,
+    pub old: u8,
+    pub new: u8,
+}
+
+impl RegisterDump {
+    pub fn new(values: Vec<(&'static Register, u8)>) -> RegisterDump {
+        RegisterDump { values }
+    }
+
+    pub fn get_values(&self) -> &[(&'static Register, u8)] {
+        self.values.as_slice()
+    }
+
+    pub fn get_value(&self, name: &str) -> Option<u8> {
+        self.values
+            .iter()
+            .find(|(register, _)| register.name == name)
+            .map(|(_, value)| *value)
+    }
+
+    pub fn diff(&self, other: &RegisterDump) -> Vec<RegisterDiff> {
+        let mut diffs = vec![];
+        for (register, old) in self.values.iter() {
+            let new = match other.get_value(register.name) {
+                Some(new) if new != *old => new,
+                _ => continue,
+            };
+            for field in register.fields {
+                if field.extract(*old) != field.extract(new) {
+                    diffs.push(RegisterDiff {
+                        register: register.name,
+                        field: field.name,
+                        old: field.extract(*old),
+                        new: field.extract(new),
+                    });
+                }
+            }
+        }
+        diffs
+    }
+}
+
+impl std::fmt::Display for RegisterDump {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (register, value) in self.values.iter() {
+            writeln!(
+                f,
+                "0x{:03X} {:<26} 0x{:02X}",
+                register.address, register.name, value
+            )?;
+            for field in register.fields {
+                writeln!(f, "      {:<32} {}", field.name, field.extract(*value))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mask(register: &str, field: &str) -> u8 {
+        find_register(register)
+            .unwrap()
+            .find_field(field)
+            .unwrap()
+            .get_mask()
+    }
+
+    #[test]
+    fn calibration_ctrl_fields() {
+        // UG-671 register 0x016 and ad9361_regs.h
+        let register = find_register_by_address(0x016).unwrap();
+        assert_eq!(register.name, "calibration_ctrl");
+        for (name, bit) in [
+            ("rx_bb_tune", 7),
+            ("tx_bb_tune", 6),
+            ("must_be_zero", 5),
+            ("tx_quad_cal", 4),
+            ("rx_gain_step_cal", 3),
+            ("tx_cal", 2),
+            ("dc_cal_rf_start", 1),
+            ("dc_cal_bb_start", 0),
+        ] {
+            assert_eq!(mask("calibration_ctrl", name), 1 << bit, "{}", name);
+        }
+    }
+
+    #[test]
+    fn fields_do_not_overlap() {
+        for register in AD9361_REGISTERS.iter() {
+            let mut used = 0u8;
+            for field in register.fields.iter() {
+                assert_eq!(
+                    used & field.get_mask(),
+                    0,
+                    "{} {}",
+                    register.name,
+                    field.name
+                );
+                used |= field.get_mask();
+            }
+        }
+    }
+
+    #[test]
+    fn field_insert_extract() {
+        let field = find_register("state")
+            .unwrap()
+            .find_field("calibration_state")
+            .unwrap();
+        assert_eq!(field.get_mask(), 0xf0);
+        assert_eq!(field.extract(0xa5), 0x0a);
+        assert_eq!(field.insert(0xa5, 0x03), Ok(0x35));
+        assert_eq!(field.insert(0xa5, 0x10), Err(()));
+
+        let field = find_register("calibration_ctrl")
+            .unwrap()
+            .find_field("dc_cal_rf_start")
+            .unwrap();
+        assert_eq!(field.extract(0x02), 1);
+        assert_eq!(field.extract(0x01), 0);
+        assert_eq!(field.insert(0x00, 1), Ok(0x02));
+        assert_eq!(field.insert(0xff, 0), Ok(0xfd));
+    }
+}
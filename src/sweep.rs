@@ -0,0 +1,188 @@
+use crate::datatypes;
+use crate::traits;
+use crate::types;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SweepFrequencies {
+    Range { start: u64, stop: u64, step: u64 },
+    List(Vec<u64>),
+}
+
+impl SweepFrequencies {
+    pub fn get_frequencies(&self) -> Result<Vec<u64>, ()> {
+        match self {
+            Self::Range { start, stop, step } => {
+                if *step == 0 || stop < start {
+                    return Err(());
+                }
+                Ok((*start..=*stop).step_by(*step as usize).collect())
+            }
+            Self::List(frequencies) => Ok(frequencies.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepPoint {
+    pub frequency: u64,
+    pub elapsed: std::time::Duration,
+    pub tone_power_db: Option<f64>,
+    pub total_power_db: Option<f64>,
+}
+
+// Full scale of the 12-bit AD9361 ADC
+static RX_FULL_SCALE: f64 = 2048.0;
+
+fn to_dbfs(amplitude: f64) -> f64 {
+    20.0 * (amplitude / RX_FULL_SCALE).max(f64::MIN_POSITIVE).log10()
+}
+
+// Single-bin DFT of the capture at the given baseband frequency
+fn tone_amplitude(samples: &[datatypes::PlutoComplex], frequency: f64, sample_rate: f64) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let omega = -2.0 * std::f64::consts::PI * frequency / sample_rate;
+    let sum = samples.iter().enumerate().fold(
+        num::complex::Complex::<f64>::new(0.0, 0.0),
+        |acc, (n, f)| {
+            let sample = num::complex::Complex::<f64>::new(f.re as f64, f.im as f64);
+            acc + sample * num::complex::Complex::<f64>::from_polar(1.0, omega * n as f64)
+        },
+    );
+    sum.norm() / samples.len() as f64
+}
+
+fn rms_amplitude(samples: &[datatypes::PlutoComplex]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let power = samples.iter().map(|f| f.norm_sqr() as f64).sum::<f64>() / samples.len() as f64;
+    power.sqrt()
+}
+
+// Rejects the whole schedule up front, failing halfway would leave the DDS half-swept
+fn check_schedule<'a, T: traits::DDS<'a>>(
+    dds: &T,
+    frequencies: &[u64],
+    channel: i32,
+) -> Result<Vec<types::dds_tone::DdsTone>, ()> {
+    let tones = dds.get_dds_tones()?;
+    if channel < 0 || !tones.iter().any(|f| f.get_tx_channel() == channel as usize) {
+        return Err(());
+    }
+    let nyquist = dds.get_dds_sample_rate()? / 2;
+    if frequencies
+        .iter()
+        .any(|f| *f > nyquist || i32::try_from(*f).is_err())
+    {
+        return Err(());
+    }
+    Ok(tones)
+}
+
+impl<'a, T> traits::DdsSweep<'a> for T
+where
+    T: traits::DDS<'a> + traits::RxCore<'a> + traits::AD9364<'a>,
+{
+    fn dds_sweep(
+        &mut self,
+        frequencies: &SweepFrequencies,
+        scale: f32,
+        channel: i32,
+        dwell: std::time::Duration,
+        capture: bool,
+    ) -> Result<Vec<SweepPoint>, ()> {
+        let frequency_list = frequencies.get_frequencies()?;
+        check_schedule(self, frequency_list.as_slice(), channel)?;
+        let sample_rate = if capture {
+            self.get_sample_rate().map_err(|_| ())? as f64
+        } else {
+            0.0
+        };
+
+        let start = std::time::Instant::now();
+        let mut points = vec![];
+        for frequency in frequency_list {
+            let step_start = std::time::Instant::now();
+            let tone_frequency = i32::try_from(frequency).map_err(|_| ())?;
+            self.dds_single_tone(tone_frequency, scale, channel)?;
+            let elapsed = start.elapsed();
+
+            let remaining = dwell.saturating_sub(step_start.elapsed());
+            std::thread::sleep(remaining);
+
+            let (tone_power_db, total_power_db) = if capture {
+                // The first buffer may still hold samples queued before the retune
+                self.rx_complex()?;
+                let data = self.rx_complex()?;
+                let samples = data.first().ok_or(())?;
+                // The sign of the received tone depends on the I/Q phase convention,
+                // keep whichever side of the spectrum carries it
+                let amplitude = tone_amplitude(samples, frequency as f64, sample_rate)
+                    .max(tone_amplitude(samples, -(frequency as f64), sample_rate));
+                (
+                    Some(to_dbfs(amplitude)),
+                    Some(to_dbfs(rms_amplitude(samples))),
+                )
+            } else {
+                (None, None)
+            };
+
+            points.push(SweepPoint {
+                frequency,
+                elapsed,
+                tone_power_db,
+                total_power_db,
+            });
+        }
+
+        Ok(points)
+    }
+
+    fn dds_chirp(
+        &mut self,
+        start: u64,
+        stop: u64,
+        scale: f32,
+        channel: i32,
+        duration: std::time::Duration,
+        update_interval: std::time::Duration,
+    ) -> Result<usize, ()> {
+        if update_interval.is_zero() {
+            return Err(());
+        }
+        let tones = check_schedule(self, &[start, stop], channel)?;
+
+        self.dds_single_tone(start as i32, scale, channel)?;
+        // Only the first tone of the channel moves, the others keep what dds_single_tone set
+        let base_frequencies = self.get_dds_frequencies()?;
+        let chirp_tone = tones
+            .iter()
+            .position(|f| f.get_tx_channel() == channel as usize && f.get_tone() == 0)
+            .ok_or(())?;
+        if chirp_tone >= base_frequencies.len() {
+            return Err(());
+        }
+
+        let chirp_start = std::time::Instant::now();
+        let mut updates = 1;
+        loop {
+            let next_update = update_interval * updates as u32;
+            if next_update >= duration {
+                break;
+            }
+            std::thread::sleep(next_update.saturating_sub(chirp_start.elapsed()));
+
+            // Follow the real elapsed time so that late updates do not slow the ramp down
+            let progress = (chirp_start.elapsed().as_secs_f64() / duration.as_secs_f64()).min(1.0);
+            let frequency = start as f64 + (stop as f64 - start as f64) * progress;
+            let mut frequencies = base_frequencies.clone();
+            frequencies[chirp_tone] = frequency.round() as u64;
+            self.set_dds_frequencies(frequencies)?;
+            updates += 1;
+        }
+
+        Ok(updates)
+    }
+}
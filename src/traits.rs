@@ -1,4 +1,8 @@
 use crate::datatypes;
+use crate::error;
+use crate::fastlock;
+use crate::fir;
+use crate::registers;
 use crate::types;
 
 pub type RxCoreInitResult = Result<
@@ -25,9 +29,8 @@ pub type TxCoreInitResult = Result<
         Option<datatypes::SdrDataType>,
         bool,
         Vec<u32>,
-        String,
+        Option<types::file_sink::FileSink>,
         Option<types::buffer::Buffer>,
-        bool,
         u32,
         usize,
     ),
@@ -91,19 +94,19 @@ pub trait SharedDef<'a> {
 
 pub trait Attribute<'a> {
     fn set_iio_attr_str(
-        &'a self,
+        &self,
         channel_name: &str,
-        attr_name: &'a str,
+        attr_name: &str,
         output: Option<bool>,
-        value: &'a str,
-        ctrl: Option<&'a types::device_trigger::DeviceTrigger>,
+        value: &str,
+        ctrl: Option<&types::device_trigger::DeviceTrigger>,
     ) -> Result<(), ()>;
     fn get_iio_attr_str(
-        &'a self,
-        channel_name: &'a str,
-        attr_name: &'a str,
+        &self,
+        channel_name: &str,
+        attr_name: &str,
         output: Option<bool>,
-        ctrl: Option<&'a types::device_trigger::DeviceTrigger>,
+        ctrl: Option<&types::device_trigger::DeviceTrigger>,
     ) -> Result<String, ()>;
     fn set_iio_dev_attr_str(
         &mut self,
@@ -117,46 +120,63 @@ pub trait Attribute<'a> {
         ctrl: Option<&mut types::device_trigger::DeviceTrigger>,
     ) -> Result<String, ()>;
     fn set_iio_debug_attr_str(
-        &self,
+        &mut self,
         attr_name: &str,
         value: &str,
-        ctrl: Option<&types::device_trigger::DeviceTrigger>,
+        ctrl: Option<&mut types::device_trigger::DeviceTrigger>,
     ) -> Result<(), ()>;
     fn get_iio_debug_attr_str(
-        &self,
+        &mut self,
         attr_name: &str,
-        ctrl: Option<&types::device_trigger::DeviceTrigger>,
+        ctrl: Option<&mut types::device_trigger::DeviceTrigger>,
     ) -> Result<String, ()>;
     fn set_iio_attr_int<T: Into<i128>>(
-        &'a self,
+        &self,
         channel_name: &str,
-        attr_name: &'a str,
+        attr_name: &str,
         output: Option<bool>,
         value: T,
-        ctrl: Option<&'a types::device_trigger::DeviceTrigger>,
+        ctrl: Option<&types::device_trigger::DeviceTrigger>,
     ) -> Result<(), ()>;
     fn get_iio_attr_int(
-        &'a self,
-        channel_name: &'a str,
-        attr_name: &'a str,
+        &self,
+        channel_name: &str,
+        attr_name: &str,
         output: Option<bool>,
-        ctrl: Option<&'a types::device_trigger::DeviceTrigger>,
+        ctrl: Option<&types::device_trigger::DeviceTrigger>,
     ) -> Result<i128, ()>;
     fn set_iio_attr_float<T: Into<f64>>(
-        &'a self,
+        &self,
         channel_name: &str,
-        attr_name: &'a str,
+        attr_name: &str,
         output: Option<bool>,
         value: T,
-        ctrl: Option<&'a types::device_trigger::DeviceTrigger>,
+        ctrl: Option<&types::device_trigger::DeviceTrigger>,
     ) -> Result<(), ()>;
     fn get_iio_attr_float(
-        &'a self,
-        channel_name: &'a str,
-        attr_name: &'a str,
+        &self,
+        channel_name: &str,
+        attr_name: &str,
         output: Option<bool>,
-        ctrl: Option<&'a types::device_trigger::DeviceTrigger>,
+        ctrl: Option<&types::device_trigger::DeviceTrigger>,
     ) -> Result<f64, ()>;
+    fn apply_iio_attr_transaction(
+        &mut self,
+        transaction: &crate::transaction::AttrTransaction,
+    ) -> Result<(), crate::error::AdiError>;
+    fn get_iio_attr_available(
+        &mut self,
+        descriptor: &crate::attribute::AttrDescriptor,
+    ) -> Result<Option<crate::attribute::AttrAvailable>, ()>;
+    fn get_iio_attr_typed(
+        &mut self,
+        descriptor: &crate::attribute::AttrDescriptor,
+    ) -> Result<crate::attribute::AttrValue, ()>;
+    fn set_iio_attr_typed(
+        &mut self,
+        descriptor: &crate::attribute::AttrDescriptor,
+        value: crate::attribute::AttrValue,
+    ) -> Result<(), crate::error::AdiError>;
 }
 
 pub trait RxTxCommon<'a> {
@@ -165,27 +185,31 @@ pub trait RxTxCommon<'a> {
 }
 
 pub trait DDS<'a> {
-    fn init() -> bool;
+    fn init(context: &types::context::Context, tx_data_device_name: &str) -> Result<bool, ()>;
+    fn get_dds_tones(&self) -> Result<Vec<types::dds_tone::DdsTone>, ()>;
     fn update_dds(&self, attr: &str, value: Vec<types::traits::DdsValue>) -> Result<(), ()>;
-    fn read_dds(&self, attr: &str) -> &str;
+    fn read_dds(&self, attr: &str) -> Result<Vec<String>, ()>;
     fn disable_dds(&self) -> Result<(), ()>;
-    fn get_dds_frequencies(&self) -> &str;
-    fn set_dds_frequencies(&self, value: &str);
-    fn get_dds_scales(&self) -> &str;
-    fn set_dds_scales(&self, value: &str);
-    fn get_dds_phases(&self) -> &str;
-    fn set_dds_phases(&self, value: &str);
-    fn get_dds_enabled(&self) -> &str;
+    fn get_dds_frequencies(&self) -> Result<Vec<u64>, ()>;
+    fn set_dds_frequencies(&self, value: Vec<u64>) -> Result<(), ()>;
+    fn get_dds_scales(&self) -> Result<Vec<f64>, ()>;
+    fn set_dds_scales(&self, value: Vec<f64>) -> Result<(), ()>;
+    fn get_dds_phases(&self) -> Result<Vec<u32>, ()>;
+    fn set_dds_phases(&self, value: Vec<u32>) -> Result<(), ()>;
+    fn get_dds_phases_radians(&self) -> Result<Vec<f64>, ()>;
+    fn set_dds_phases_radians(&self, value: Vec<f64>) -> Result<(), ()>;
+    fn get_dds_enabled(&self) -> Result<Vec<bool>, ()>;
     fn set_dds_enabled(&self, value: Vec<bool>) -> Result<(), ()>;
-    fn dds_single_tone(&self, frequency: i32, scale: f32, channel: i32);
+    fn get_dds_sample_rate(&self) -> Result<u64, ()>;
+    fn dds_single_tone(&mut self, frequency: i32, scale: f32, channel: i32) -> Result<(), ()>;
     fn dds_dual_tone(
-        &self,
+        &mut self,
         frequency1: i32,
         scale1: f32,
-        frequncy2: i32,
+        frequency2: i32,
         scale2: f32,
         channel: i32,
-    );
+    ) -> Result<(), ()>;
 }
 
 pub trait TxCore<'a> {
@@ -201,8 +225,18 @@ pub trait TxCore<'a> {
     fn get_tx_channel_names(&self) -> Vec<&str>;
     fn get_tx_enabled_channels(&self) -> Vec<i32>;
     fn set_tx_enabled_channels(&self, value: Vec<i32>);
+    fn get_tx_file_sink(&self) -> Option<&types::file_sink::FileSink>;
+    fn set_tx_file_sink(&mut self, value: Option<types::file_sink::FileSink>);
     fn tx_destroy_buffer(&mut self);
+    // PlutoComplex samples are full scale at ±1.0, see datatypes::TxSample
     fn tx(&mut self, data_opt: Option<Vec<Vec<datatypes::PlutoComplex>>>) -> Result<(), ()>;
+    // Samples must match the buffer: complex types for complex data, i16 for real data
+    fn tx_from_slices<T: datatypes::TxSample>(&mut self, data: &[&[T]]) -> Result<(), ()>;
+    fn tx_interleaved<T: datatypes::TxSample>(&mut self, data: &[T]) -> Result<(), ()>;
+    fn tx_interleaved_iter<T: datatypes::TxSample, I: ExactSizeIterator<Item = T>>(
+        &mut self,
+        data: I,
+    ) -> Result<(), ()>;
     fn tx_buffer_push(&mut self, data: Vec<u8>) -> Result<(), ()>;
     fn tx_init_channels(&mut self) -> Result<(), ()>;
 }
@@ -259,24 +293,197 @@ pub trait RxTxDef<'a> {
 
 pub trait AD9364<'a> {
     fn init() -> Ad9364InitResult;
-    fn get_filter(&self) -> Vec<i32>;
-    fn set_filter(&self, filename: &str);
-    fn get_loopback(&self) -> Vec<i32>;
-    fn set_loopback(&self, value: datatypes::Loopback);
-    fn get_gain_control_mode_chan0(&'a self) -> Result<String, ()>;
-    fn set_gain_control_mode_chan0(&'a self, value: &'a str) -> Result<(), ()>;
+    fn get_cached_filter(&mut self) -> Result<Option<fir::FirFilter>, ()>;
+    fn set_filter(&mut self, filename: &str) -> Result<(), ()>;
+    fn set_fir_filter(&mut self, filter: fir::FirFilter) -> Result<(), ()>;
+    fn get_loopback(&mut self) -> Result<datatypes::Loopback, ()>;
+    fn set_loopback(&mut self, value: datatypes::Loopback) -> Result<(), ()>;
+    fn get_gain_control_mode_chan0(&self) -> Result<String, ()>;
+    fn set_gain_control_mode_chan0(&self, value: &str) -> Result<(), ()>;
     fn get_rx_hardwaregain_chan0(&self) -> Result<f32, ()>;
     fn set_rx_hardwaregain_chan0(&self, value: f32) -> Result<(), ()>;
     fn get_tx_hardwaregain_chan0(&self) -> Result<f32, ()>;
     fn set_tx_hardwaregain_chan0(&self, value: f32) -> Result<(), ()>;
-    fn get_rx_rf_bandwidth(&self) -> Result<u32, ()>;
-    fn set_rx_rf_bandwidth(&self, value: u32) -> Result<(), ()>;
-    fn get_tx_rf_bandwidth(&self) -> Result<u32, ()>;
-    fn set_tx_rf_bandwidth(&self, value: u32) -> Result<(), ()>;
-    fn get_sample_rate(&self) -> Result<u32, ()>;
-    fn set_sample_rate(&mut self, value: u32) -> Result<(), ()>;
-    fn get_rx_lo(&self) -> Result<u64, ()>;
-    fn set_rx_lo(&self, value: u64) -> Result<(), ()>;
-    fn get_tx_lo(&self) -> Result<u64, ()>;
-    fn set_tx_lo(&self, value: u64) -> Result<(), ()>;
+    fn get_gain_control_modes_available(
+        &self,
+    ) -> Result<Vec<datatypes::GainControlMode>, error::AdiError>;
+    fn get_gain_control_mode(
+        &self,
+        channel: usize,
+    ) -> Result<datatypes::GainControlMode, error::AdiError>;
+    fn set_gain_control_mode(
+        &self,
+        channel: usize,
+        mode: datatypes::GainControlMode,
+    ) -> Result<(), error::AdiError>;
+    fn get_rx_hardwaregain_available(
+        &self,
+        channel: usize,
+    ) -> Result<datatypes::AttrRange, error::AdiError>;
+    fn get_rx_hardwaregain(&self, channel: usize) -> Result<f64, error::AdiError>;
+    fn set_rx_hardwaregain(&self, channel: usize, value: f64) -> Result<(), error::AdiError>;
+    fn get_tx_hardwaregain_available(
+        &self,
+        channel: usize,
+    ) -> Result<datatypes::AttrRange, error::AdiError>;
+    fn get_tx_hardwaregain(&self, channel: usize) -> Result<f64, error::AdiError>;
+    fn set_tx_hardwaregain(&self, channel: usize, value: f64) -> Result<(), error::AdiError>;
+    fn get_rssi(&self, channel: usize) -> Result<f64, error::AdiError>;
+    fn get_temperature(&self) -> Result<f64, error::AdiError>;
+    fn get_quadrature_tracking(&self) -> Result<bool, error::AdiError>;
+    fn set_quadrature_tracking(&self, value: bool) -> Result<(), error::AdiError>;
+    fn get_rf_dc_offset_tracking(&self) -> Result<bool, error::AdiError>;
+    fn set_rf_dc_offset_tracking(&self, value: bool) -> Result<(), error::AdiError>;
+    fn get_bb_dc_offset_tracking(&self) -> Result<bool, error::AdiError>;
+    fn set_bb_dc_offset_tracking(&self, value: bool) -> Result<(), error::AdiError>;
+    fn get_calibration_modes_available(
+        &mut self,
+    ) -> Result<Vec<datatypes::CalibrationMode>, error::AdiError>;
+    fn get_calibration_mode(&mut self) -> Result<datatypes::CalibrationMode, error::AdiError>;
+    fn set_calibration_mode(
+        &mut self,
+        mode: datatypes::CalibrationMode,
+    ) -> Result<(), error::AdiError>;
+    fn run_calibration(
+        &mut self,
+        mode: datatypes::CalibrationMode,
+        timeout: std::time::Duration,
+    ) -> Result<std::time::Duration, error::AdiError>;
+    fn get_ensm_modes_available(&mut self) -> Result<Vec<datatypes::EnsmMode>, error::AdiError>;
+    fn get_ensm_mode(&mut self) -> Result<datatypes::EnsmMode, error::AdiError>;
+    fn set_ensm_mode(&mut self, mode: datatypes::EnsmMode) -> Result<(), error::AdiError>;
+    fn get_fastlock_profiles(&self) -> &fastlock::FastlockStore;
+    fn fastlock_store(
+        &mut self,
+        synthesizer: datatypes::Synthesizer,
+        index: u8,
+    ) -> Result<fastlock::FastlockProfile, ()>;
+    fn fastlock_recall(&mut self, synthesizer: datatypes::Synthesizer, index: u8)
+    -> Result<(), ()>;
+    fn fastlock_load(&mut self, profile: fastlock::FastlockProfile) -> Result<(), ()>;
+    fn save_fastlock_profiles(&self, filename: &str) -> Result<(), ()>;
+    fn load_fastlock_profiles(&mut self, filename: &str) -> Result<(), ()>;
+    fn get_rx_rf_ports_wired(&self) -> &'static [datatypes::RxPort];
+    fn get_tx_rf_ports_wired(&self) -> &'static [datatypes::TxPort];
+    fn get_rx_rf_ports_available(&self) -> Result<Vec<datatypes::RxPort>, error::AdiError>;
+    fn get_rx_rf_port(&self) -> Result<datatypes::RxPort, error::AdiError>;
+    fn set_rx_rf_port(&self, port: datatypes::RxPort) -> Result<(), error::AdiError>;
+    fn get_tx_rf_ports_available(&self) -> Result<Vec<datatypes::TxPort>, error::AdiError>;
+    fn get_tx_rf_port(&self) -> Result<datatypes::TxPort, error::AdiError>;
+    fn set_tx_rf_port(&self, port: datatypes::TxPort) -> Result<(), error::AdiError>;
+    fn set_rf_ports_for_lo(
+        &self,
+    ) -> Result<(datatypes::RxPort, datatypes::TxPort), error::AdiError>;
+    fn get_rx_rf_bandwidth(&self) -> Result<u32, error::AdiError>;
+    fn set_rx_rf_bandwidth(&self, value: u32) -> Result<(), error::AdiError>;
+    fn get_tx_rf_bandwidth(&self) -> Result<u32, error::AdiError>;
+    fn set_tx_rf_bandwidth(&self, value: u32) -> Result<(), error::AdiError>;
+    fn get_sample_rate(&self) -> Result<u32, error::AdiError>;
+    fn set_sample_rate(&mut self, value: u32) -> Result<(), error::AdiError>;
+    fn set_sample_rate_designed(&mut self, design: fir::FirDesign) -> Result<(), ()>;
+    fn get_rx_path_rates(&mut self) -> Result<datatypes::PathRates, ()>;
+    fn get_tx_path_rates(&mut self) -> Result<datatypes::PathRates, ()>;
+    fn get_rx_lo(&self) -> Result<u64, error::AdiError>;
+    fn set_rx_lo(&self, value: u64) -> Result<(), error::AdiError>;
+    fn get_tx_lo(&self) -> Result<u64, error::AdiError>;
+    fn set_tx_lo(&self, value: u64) -> Result<(), error::AdiError>;
+    fn get_radio_ranges(&self) -> &datatypes::RadioRanges;
+    fn get_lo_external(&self, synthesizer: datatypes::Synthesizer)
+    -> Result<bool, error::AdiError>;
+    fn set_lo_external(
+        &mut self,
+        synthesizer: datatypes::Synthesizer,
+        value: bool,
+    ) -> Result<(), error::AdiError>;
+    fn get_lo_powerdown(
+        &self,
+        synthesizer: datatypes::Synthesizer,
+    ) -> Result<bool, error::AdiError>;
+    fn set_lo_powerdown(
+        &mut self,
+        synthesizer: datatypes::Synthesizer,
+        value: bool,
+    ) -> Result<(), error::AdiError>;
+    fn get_hw_serial(&self) -> Option<String>;
+    fn get_xo_correction_available(&mut self) -> Result<datatypes::AttrRange, error::AdiError>;
+    fn get_xo_correction(&mut self) -> Result<u64, error::AdiError>;
+    fn set_xo_correction(&mut self, value: u64) -> Result<(), error::AdiError>;
+}
+
+pub trait DdsSweep<'a> {
+    fn dds_sweep(
+        &mut self,
+        frequencies: &crate::sweep::SweepFrequencies,
+        scale: f32,
+        channel: i32,
+        dwell: std::time::Duration,
+        capture: bool,
+    ) -> Result<Vec<crate::sweep::SweepPoint>, ()>;
+    fn dds_chirp(
+        &mut self,
+        start: u64,
+        stop: u64,
+        scale: f32,
+        channel: i32,
+        duration: std::time::Duration,
+        update_interval: std::time::Duration,
+    ) -> Result<usize, ()>;
+}
+
+pub trait Registers<'a> {
+    fn reg_read(&mut self, address: u32) -> Result<u32, ()>;
+    fn reg_write(&mut self, address: u32, value: u32) -> Result<(), ()>;
+    fn read_register(&mut self, name: &str) -> Result<u8, ()>;
+    fn write_register(&mut self, name: &str, value: u8) -> Result<(), ()>;
+    fn read_register_field(&mut self, register: &str, field: &str) -> Result<u8, ()>;
+    fn write_register_field(&mut self, register: &str, field: &str, value: u8) -> Result<(), ()>;
+    fn dump_registers(&mut self) -> Result<registers::RegisterDump, ()>;
+}
+
+pub trait HealthMonitor<'a> {
+    fn read_health(
+        &self,
+        channels: &[usize],
+    ) -> Result<crate::monitor::HealthSample, error::AdiError>;
+    fn monitor_health(
+        &self,
+        channels: &[usize],
+        interval: std::time::Duration,
+        count: usize,
+    ) -> Result<Vec<crate::monitor::HealthSample>, error::AdiError>;
+}
+
+pub trait FrequencyHopper<'a> {
+    fn frequency_hop(
+        &mut self,
+        frequencies: &[u64],
+        synthesizers: &[datatypes::Synthesizer],
+        dwell: std::time::Duration,
+        cycles: usize,
+        capture: bool,
+        lock_timeout: Option<std::time::Duration>,
+    ) -> Result<Vec<crate::hopping::Hop>, ()>;
+}
+
+pub trait XoCalibrate<'a> {
+    fn calibrate_xo(
+        &mut self,
+        reference: crate::xo::XoReference,
+        iterations: usize,
+    ) -> Result<crate::xo::XoCalibration, error::AdiError>;
+    fn calibrate_xo_persist(
+        &mut self,
+        reference: crate::xo::XoReference,
+        iterations: usize,
+        filename: &str,
+    ) -> Result<crate::xo::XoCalibration, error::AdiError>;
+    fn apply_xo_calibration(&mut self, filename: &str) -> Result<bool, error::AdiError>;
+}
+
+pub trait ConfigSnapshot<'a> {
+    fn snapshot_config(&mut self) -> Result<crate::config::RadioConfig, error::AdiError>;
+    fn restore_config(
+        &mut self,
+        config: &crate::config::RadioConfig,
+    ) -> Result<(), error::AdiError>;
 }
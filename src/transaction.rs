@@ -0,0 +1,330 @@
+use crate::error;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttrTarget {
+    Channel { channel_name: String, output: bool },
+    // Channel of the streaming core, the RX one for inputs and the TX one for outputs
+    DataChannel { channel_name: String, output: bool },
+    Device,
+    Debug,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttrWrite {
+    pub target: AttrTarget,
+    pub attr_name: String,
+    pub value: String,
+    // Value restored on rollback, read from the device when not given. Required
+    // for attributes whose read-back is not writable, see NO_READ_BACK_ATTRS
+    pub rollback: Option<String>,
+}
+
+// Attributes that read back as status text instead of the value written
+pub static NO_READ_BACK_ATTRS: [&str; 1] = ["filter_fir_config"];
+
+impl AttrWrite {
+    pub fn needs_rollback(&self) -> bool {
+        self.rollback.is_none() && NO_READ_BACK_ATTRS.contains(&self.attr_name.as_str())
+    }
+
+    // Name in the sysfs form, e.g. in_voltage0_sampling_frequency
+    pub fn get_name(&self) -> String {
+        match &self.target {
+            AttrTarget::Channel {
+                channel_name,
+                output,
+            } => format!(
+                "{}_{}_{}",
+                if *output { "out" } else { "in" },
+                channel_name,
+                self.attr_name
+            ),
+            AttrTarget::DataChannel {
+                channel_name,
+                output,
+            } => format!(
+                "{} core {}_{}_{}",
+                if *output { "tx" } else { "rx" },
+                if *output { "out" } else { "in" },
+                channel_name,
+                self.attr_name
+            ),
+            AttrTarget::Device => self.attr_name.clone(),
+            AttrTarget::Debug => format!("debug {}", self.attr_name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AttrTransaction {
+    writes: Vec<AttrWrite>,
+}
+
+impl AttrTransaction {
+    pub fn new() -> AttrTransaction {
+        AttrTransaction { writes: vec![] }
+    }
+
+    pub fn channel(
+        mut self,
+        channel_name: &str,
+        output: bool,
+        attr_name: &str,
+        value: &str,
+    ) -> Self {
+        self.writes.push(AttrWrite {
+            target: AttrTarget::Channel {
+                channel_name: channel_name.to_owned(),
+                output,
+            },
+            attr_name: attr_name.to_owned(),
+            value: value.to_owned(),
+            rollback: None,
+        });
+        self
+    }
+
+    pub fn data_channel(
+        mut self,
+        channel_name: &str,
+        output: bool,
+        attr_name: &str,
+        value: &str,
+    ) -> Self {
+        self.writes.push(AttrWrite {
+            target: AttrTarget::DataChannel {
+                channel_name: channel_name.to_owned(),
+                output,
+            },
+            attr_name: attr_name.to_owned(),
+            value: value.to_owned(),
+            rollback: None,
+        });
+        self
+    }
+
+    pub fn device(mut self, attr_name: &str, value: &str) -> Self {
+        self.writes.push(AttrWrite {
+            target: AttrTarget::Device,
+            attr_name: attr_name.to_owned(),
+            value: value.to_owned(),
+            rollback: None,
+        });
+        self
+    }
+
+    pub fn debug(mut self, attr_name: &str, value: &str) -> Self {
+        self.writes.push(AttrWrite {
+            target: AttrTarget::Debug,
+            attr_name: attr_name.to_owned(),
+            value: value.to_owned(),
+            rollback: None,
+        });
+        self
+    }
+
+    // Overrides the rollback value of the last write added
+    pub fn rollback_to(mut self, value: &str) -> Self {
+        if let Some(write) = self.writes.last_mut() {
+            write.rollback = Some(value.to_owned());
+        }
+        self
+    }
+
+    pub fn push(&mut self, write: AttrWrite) {
+        self.writes.push(write);
+    }
+
+    pub fn get_writes(&self) -> &[AttrWrite] {
+        self.writes.as_slice()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.writes.len()
+    }
+
+    // Writes in order, on failure restores the applied writes in reverse order so
+    // dependent attributes see the states they were written in
+    pub fn apply<T>(
+        &self,
+        target: &mut T,
+        read: fn(&mut T, &AttrWrite) -> Result<String, ()>,
+        write: fn(&mut T, &AttrWrite, &str) -> Result<(), ()>,
+    ) -> Result<(), error::AdiError> {
+        if let Some(f) = self.writes.iter().find(|f| f.needs_rollback()) {
+            return Err(error::AdiError::MissingRollbackError(f.get_name()));
+        }
+
+        let mut applied = vec![];
+        let mut result = Ok(());
+        for f in self.writes.iter() {
+            let previous = match &f.rollback {
+                Some(value) => Ok(value.clone()),
+                None => read(target, f),
+            };
+            // Nothing to roll back to means the write cannot be undone, stop before it
+            let Ok(previous) = previous else {
+                result = Err(error::AdiError::IioError);
+                break;
+            };
+            if write(target, f, f.value.as_str()).is_err() {
+                result = Err(error::AdiError::IioError);
+                break;
+            }
+            applied.push((f, previous));
+        }
+
+        if result.is_err() {
+            let mut modified = vec![];
+            for (f, previous) in applied.iter().rev() {
+                if write(target, f, previous.as_str()).is_err() {
+                    modified.push(f.get_name());
+                }
+            }
+            if !modified.is_empty() {
+                return Err(error::AdiError::RollbackError(modified));
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Attribute values by name, writes of values in `fail` are refused
+    #[derive(Default)]
+    struct MockTarget {
+        values: std::collections::HashMap<String, String>,
+        fail: Vec<String>,
+        log: Vec<String>,
+    }
+
+    fn mock_read(target: &mut MockTarget, write: &AttrWrite) -> Result<String, ()> {
+        target.values.get(&write.get_name()).cloned().ok_or(())
+    }
+
+    fn mock_write(target: &mut MockTarget, write: &AttrWrite, value: &str) -> Result<(), ()> {
+        target.log.push(format!("{}={}", write.get_name(), value));
+        if target.fail.iter().any(|f| f == value) {
+            return Err(());
+        }
+        target.values.insert(write.get_name(), value.to_owned());
+        Ok(())
+    }
+
+    fn mock_target() -> MockTarget {
+        let mut target = MockTarget::default();
+        for (name, value) in [
+            ("in_voltage0_sampling_frequency", "30720000"),
+            ("out_voltage0_sampling_frequency", "30720000"),
+            ("ensm_mode", "fdd"),
+        ] {
+            target.values.insert(name.to_owned(), value.to_owned());
+        }
+        target
+    }
+
+    fn transaction() -> AttrTransaction {
+        AttrTransaction::new()
+            .device("ensm_mode", "alert")
+            .channel("voltage0", false, "sampling_frequency", "2083334")
+            .channel("voltage0", true, "sampling_frequency", "2083333")
+    }
+
+    #[test]
+    fn apply_in_order() {
+        let mut target = mock_target();
+        assert_eq!(
+            transaction().apply(&mut target, mock_read, mock_write),
+            Ok(())
+        );
+        assert_eq!(
+            target.log,
+            [
+                "ensm_mode=alert",
+                "in_voltage0_sampling_frequency=2083334",
+                "out_voltage0_sampling_frequency=2083333",
+            ]
+        );
+    }
+
+    #[test]
+    fn rollback_in_reverse_order() {
+        let mut target = mock_target();
+        target.fail = vec!["2083333".to_owned()];
+        assert_eq!(
+            transaction().apply(&mut target, mock_read, mock_write),
+            Err(error::AdiError::IioError)
+        );
+        assert_eq!(
+            target.log,
+            [
+                "ensm_mode=alert",
+                "in_voltage0_sampling_frequency=2083334",
+                "out_voltage0_sampling_frequency=2083333",
+                "in_voltage0_sampling_frequency=30720000",
+                "ensm_mode=fdd",
+            ]
+        );
+        assert_eq!(target.values, mock_target().values);
+    }
+
+    #[test]
+    fn failed_rollback_is_reported() {
+        let mut target = mock_target();
+        target.fail = vec!["2083333".to_owned(), "30720000".to_owned()];
+        assert_eq!(
+            transaction().apply(&mut target, mock_read, mock_write),
+            Err(error::AdiError::RollbackError(vec![
+                "in_voltage0_sampling_frequency".to_owned()
+            ]))
+        );
+        // The rollback carries on past a failed write
+        assert_eq!(target.log.last().unwrap(), "ensm_mode=fdd");
+        assert_eq!(target.values["ensm_mode"], "fdd");
+    }
+
+    #[test]
+    fn unreadable_attribute_is_not_written() {
+        let mut target = mock_target();
+        target.values.remove("out_voltage0_sampling_frequency");
+        assert_eq!(
+            transaction().apply(&mut target, mock_read, mock_write),
+            Err(error::AdiError::IioError)
+        );
+        assert!(
+            !target
+                .log
+                .iter()
+                .any(|f| f.starts_with("out_voltage0_sampling_frequency"))
+        );
+        assert_eq!(target.values["in_voltage0_sampling_frequency"], "30720000");
+        assert_eq!(target.values["ensm_mode"], "fdd");
+    }
+
+    #[test]
+    fn missing_rollback_value() {
+        let mut target = mock_target();
+        let transaction = AttrTransaction::new()
+            .device("ensm_mode", "alert")
+            .device("filter_fir_config", "RX 3 GAIN -6 DEC 4");
+        assert_eq!(
+            transaction.apply(&mut target, mock_read, mock_write),
+            Err(error::AdiError::MissingRollbackError(
+                "filter_fir_config".to_owned()
+            ))
+        );
+        assert!(target.log.is_empty());
+        let transaction = transaction.rollback_to("");
+        assert_eq!(
+            transaction.apply(&mut target, mock_read, mock_write),
+            Ok(())
+        );
+    }
+}
@@ -69,6 +69,13 @@ impl Buffer {
         pointer_diff
     }
 
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        let buffer_start = self.buffer.start();
+        let buffer_end = self.buffer.end();
+        let pointer_diff = unsafe { buffer_end.offset_from(buffer_start) } as usize;
+        unsafe { std::slice::from_raw_parts_mut(buffer_start, pointer_diff) }
+    }
+
     pub fn cancel(&mut self) {
         self.buffer.cancel()
     }
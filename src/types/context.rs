@@ -28,6 +28,13 @@ impl<'a> Context<'a> {
         Ok(Box::new(device_mut))
     }
 
+    pub fn find_device_mut(
+        &mut self,
+        name_or_id_or_label: &str,
+    ) -> Result<&mut iio::IIODevice, ()> {
+        self.context.find_device_mut(name_or_id_or_label)
+    }
+
     pub fn new_from_string(uri: String) -> Result<Context<'a>, ()> {
         let context_ptr = iio::IIOContext::create_from_uri(uri.as_str());
         if let Some(mut context) = context_ptr {
@@ -0,0 +1,149 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct DdsTone {
+    device_name: String,
+    channel_id: String,
+    label: Option<String>,
+    tx_channel: usize,
+    quadrature: bool,
+    tone: usize,
+}
+
+impl DdsTone {
+    // Tones are labelled TX<n>_<I|Q>_F<k>, cores without labels fall back to the
+    // altvoltage index which follows the same I F1, I F2, Q F1, Q F2 ordering
+    pub fn new(
+        device_name: String,
+        channel_id: String,
+        label: Option<String>,
+        tx_channel_offset: usize,
+    ) -> Result<DdsTone, ()> {
+        let parsed = label.as_deref().and_then(Self::parse_label);
+        let (tx_channel, quadrature, tone) = if let Some(parsed) = parsed {
+            parsed
+        } else {
+            let index = channel_id
+                .strip_prefix("altvoltage")
+                .ok_or(())?
+                .parse::<usize>()
+                .map_err(|_| ())?;
+            (
+                index / 4 + tx_channel_offset,
+                (index / 2) % 2 == 1,
+                index % 2,
+            )
+        };
+        Ok(DdsTone {
+            device_name,
+            channel_id,
+            label,
+            tx_channel,
+            quadrature,
+            tone,
+        })
+    }
+
+    fn parse_label(label: &str) -> Option<(usize, bool, usize)> {
+        let mut parts = label.split('_');
+        let tx_channel = parts.next()?.strip_prefix("TX")?.parse::<usize>().ok()?;
+        let quadrature = match parts.next()? {
+            "I" => false,
+            "Q" => true,
+            _ => return None,
+        };
+        let tone = parts.next()?.strip_prefix("F")?.parse::<usize>().ok()?;
+        if tx_channel == 0 || tone == 0 || parts.next().is_some() {
+            return None;
+        }
+        Some((tx_channel - 1, quadrature, tone - 1))
+    }
+
+    pub fn get_device_name(&self) -> &str {
+        self.device_name.as_str()
+    }
+
+    pub fn get_channel_id(&self) -> &str {
+        self.channel_id.as_str()
+    }
+
+    pub fn get_label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    pub fn get_tx_channel(&self) -> usize {
+        self.tx_channel
+    }
+
+    pub fn get_quadrature(&self) -> bool {
+        self.quadrature
+    }
+
+    pub fn get_tone(&self) -> usize {
+        self.tone
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(channel_id: &str, label: Option<&str>, offset: usize) -> Result<DdsTone, ()> {
+        DdsTone::new(
+            "cf-ad9361-dds-core-lpc".to_owned(),
+            channel_id.to_owned(),
+            label.map(|f| f.to_owned()),
+            offset,
+        )
+    }
+
+    fn position(tone: &DdsTone) -> (usize, bool, usize) {
+        (
+            tone.get_tx_channel(),
+            tone.get_quadrature(),
+            tone.get_tone(),
+        )
+    }
+
+    #[test]
+    fn label_parsing() {
+        assert_eq!(DdsTone::parse_label("TX1_I_F1"), Some((0, false, 0)));
+        assert_eq!(DdsTone::parse_label("TX1_Q_F2"), Some((0, true, 1)));
+        assert_eq!(DdsTone::parse_label("TX2_I_F2"), Some((1, false, 1)));
+        assert_eq!(DdsTone::parse_label("TX0_I_F1"), None);
+        assert_eq!(DdsTone::parse_label("TX1_I_F0"), None);
+        assert_eq!(DdsTone::parse_label("TX1_X_F1"), None);
+        assert_eq!(DdsTone::parse_label("TX1_I_F1_extra"), None);
+        assert_eq!(DdsTone::parse_label("RX1_I_F1"), None);
+        assert_eq!(DdsTone::parse_label("TX1_I"), None);
+    }
+
+    #[test]
+    fn label_ignores_offset() {
+        let labelled = tone("altvoltage0", Some("TX2_Q_F1"), 1).unwrap();
+        assert_eq!(position(&labelled), (1, true, 0));
+        assert_eq!(labelled.get_label(), Some("TX2_Q_F1"));
+    }
+
+    #[test]
+    fn index_fallback() {
+        let positions = (0..8)
+            .map(|f| position(&tone(format!("altvoltage{}", f).as_str(), None, 0).unwrap()))
+            .collect::<Vec<(usize, bool, usize)>>();
+        assert_eq!(
+            positions,
+            vec![
+                (0, false, 0),
+                (0, false, 1),
+                (0, true, 0),
+                (0, true, 1),
+                (1, false, 0),
+                (1, false, 1),
+                (1, true, 0),
+                (1, true, 1),
+            ]
+        );
+        // Secondary cores number their tones from 0 again
+        let secondary = tone("altvoltage2", Some("not a tone label"), 1).unwrap();
+        assert_eq!(position(&secondary), (1, true, 0));
+        assert!(tone("voltage0", None, 0).is_err());
+    }
+}
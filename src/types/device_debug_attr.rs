@@ -44,11 +44,11 @@ impl DeviceDebugAttr {
         device.debug_attr_write(self.name.as_str(), value)
     }
 
-    fn get_value(&self, device: &iio::IIODevice) -> Result<&str, ()> {
+    pub fn get_value(&self, device: &iio::IIODevice) -> Result<&str, ()> {
         self.read(device)
     }
 
-    fn set_value(&mut self, device: &iio::IIODevice, value: &str) -> isize {
+    pub fn set_value(&mut self, device: &iio::IIODevice, value: &str) -> isize {
         self.write(device, value)
     }
 }
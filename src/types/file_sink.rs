@@ -0,0 +1,350 @@
+use std::io::Write;
+
+use crate::datatypes;
+
+#[derive(Debug)]
+pub struct FileSink {
+    path: String,
+    format: datatypes::TxFileFormat,
+    append: bool,
+    tee: bool,
+    file: Option<std::fs::File>,
+    samples_written: usize,
+    captures: Vec<(usize, Option<u64>)>,
+}
+
+impl FileSink {
+    pub fn new(path: String, format: datatypes::TxFileFormat, append: bool, tee: bool) -> FileSink {
+        FileSink {
+            path,
+            format,
+            append,
+            tee,
+            file: None,
+            samples_written: 0,
+            captures: vec![],
+        }
+    }
+
+    pub fn get_path(&self) -> &str {
+        self.path.as_str()
+    }
+
+    pub fn get_format(&self) -> datatypes::TxFileFormat {
+        self.format
+    }
+
+    pub fn get_append(&self) -> bool {
+        self.append
+    }
+
+    pub fn get_tee(&self) -> bool {
+        self.tee
+    }
+
+    pub fn get_samples_written(&self) -> usize {
+        self.samples_written
+    }
+
+    pub fn get_data_path(&self) -> String {
+        match self.format {
+            datatypes::TxFileFormat::SigMF => format!("{}.sigmf-data", self.path),
+            _ => self.path.clone(),
+        }
+    }
+
+    pub fn get_meta_path(&self) -> Option<String> {
+        match self.format {
+            datatypes::TxFileFormat::SigMF => Some(format!("{}.sigmf-meta", self.path)),
+            _ => None,
+        }
+    }
+
+    fn open(&mut self) -> Result<&mut std::fs::File, ()> {
+        if self.file.is_none() {
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .write(true)
+                .append(self.append)
+                .truncate(!self.append)
+                .open(self.get_data_path())
+                .map_err(|_| ())?;
+            self.file = Some(file);
+        }
+        self.file.as_mut().ok_or(())
+    }
+
+    // `data` holds the interleaved little-endian 16-bit words as laid out in the TX buffer
+    pub fn write(
+        &mut self,
+        data: &[u8],
+        num_channels: usize,
+        complex_data: bool,
+        sample_rate: Option<u32>,
+        frequency: Option<u64>,
+    ) -> Result<(), ()> {
+        let n = if complex_data { 2 } else { 1 };
+        let stride = num_channels * n * 2;
+        if stride == 0 || !data.len().is_multiple_of(stride) {
+            return Err(());
+        }
+
+        let format = self.format;
+        if self.file.is_none() && self.append {
+            let word_size = if format == datatypes::TxFileFormat::Cf32 {
+                4
+            } else {
+                2
+            };
+            let existing = std::fs::metadata(self.get_data_path())
+                .map(|f| f.len() as usize)
+                .unwrap_or(0);
+            self.samples_written = existing / (num_channels * n * word_size);
+            if format == datatypes::TxFileFormat::SigMF {
+                self.captures = self.load_captures(existing > 0)?;
+            }
+        }
+        let file = self.open()?;
+        match format {
+            datatypes::TxFileFormat::Cs16 | datatypes::TxFileFormat::SigMF => {
+                file.write_all(data).map_err(|_| ())?;
+            }
+            datatypes::TxFileFormat::Cf32 => {
+                // Inverse of the 2^14 scaling applied to PlutoComplex samples on the way in
+                let out_data = data
+                    .chunks_exact(2)
+                    .flat_map(|f| (i16::from_le_bytes([f[0], f[1]]) as f32 / 16384.0).to_le_bytes())
+                    .collect::<Vec<u8>>();
+                file.write_all(out_data.as_slice()).map_err(|_| ())?;
+            }
+        }
+
+        if format == datatypes::TxFileFormat::SigMF {
+            if self.captures.last().is_none_or(|f| f.1 != frequency) {
+                self.captures.push((self.samples_written, frequency));
+            }
+            self.write_meta(num_channels, complex_data, sample_rate)?;
+        }
+
+        self.samples_written += data.len() / stride;
+        Ok(())
+    }
+
+    // Appending keeps the captures of the existing recording, which are rewritten with the meta
+    fn load_captures(&self, has_data: bool) -> Result<Vec<(usize, Option<u64>)>, ()> {
+        let meta_path = self.get_meta_path().ok_or(())?;
+        match std::fs::read_to_string(meta_path) {
+            Ok(meta) => parse_captures(meta.as_str()),
+            Err(_) if !has_data => Ok(vec![]),
+            Err(_) => Err(()),
+        }
+    }
+
+    fn write_meta(
+        &self,
+        num_channels: usize,
+        complex_data: bool,
+        sample_rate: Option<u32>,
+    ) -> Result<(), ()> {
+        let meta_path = self.get_meta_path().ok_or(())?;
+        let meta = format_meta(
+            self.captures.as_slice(),
+            num_channels,
+            complex_data,
+            sample_rate,
+        )?;
+        std::fs::write(meta_path, meta).map_err(|_| ())
+    }
+}
+
+#[cfg(feature = "serde")]
+fn parse_captures(meta: &str) -> Result<Vec<(usize, Option<u64>)>, ()> {
+    let meta = serde_json::from_str::<serde_json::Value>(meta).map_err(|_| ())?;
+    let captures = meta.get("captures").and_then(|f| f.as_array()).ok_or(())?;
+    captures
+        .iter()
+        .map(|capture| {
+            let sample_start = capture
+                .get("core:sample_start")
+                .and_then(|f| f.as_u64())
+                .ok_or(())?;
+            // SigMF stores the frequency as a double, other recorders may write 2.4e9
+            let frequency = match capture.get("core:frequency") {
+                Some(f) => Some(
+                    f.as_u64()
+                        .or_else(|| {
+                            f.as_f64()
+                                .filter(|f| *f >= 0.0 && f.fract() == 0.0)
+                                .map(|f| f as u64)
+                        })
+                        .ok_or(())?,
+                ),
+                None => None,
+            };
+            Ok((sample_start as usize, frequency))
+        })
+        .collect()
+}
+
+#[cfg(feature = "serde")]
+fn format_meta(
+    captures: &[(usize, Option<u64>)],
+    num_channels: usize,
+    complex_data: bool,
+    sample_rate: Option<u32>,
+) -> Result<String, ()> {
+    let datatype = if complex_data { "ci16_le" } else { "ri16_le" };
+    let mut global = serde_json::json!({
+        "core:datatype": datatype,
+        "core:version": "1.0.0",
+        "core:num_channels": num_channels,
+        "core:hw": "PlutoSDR",
+        "core:recorder": "adi",
+    });
+    if let Some(rate) = sample_rate {
+        global["core:sample_rate"] = rate.into();
+    }
+    let captures = captures
+        .iter()
+        .map(|(sample_start, frequency)| {
+            let mut capture = serde_json::json!({ "core:sample_start": sample_start });
+            if let Some(frequency) = frequency {
+                capture["core:frequency"] = (*frequency).into();
+            }
+            capture
+        })
+        .collect::<Vec<serde_json::Value>>();
+    let meta = serde_json::json!({
+        "global": global,
+        "captures": captures,
+        "annotations": [],
+    });
+    serde_json::to_string_pretty(&meta)
+        .map(|f| f + "\n")
+        .map_err(|_| ())
+}
+
+// Without serde_json only metadata in the exact layout format_meta writes is understood,
+// anything else is refused
+#[cfg(not(feature = "serde"))]
+fn parse_captures(meta: &str) -> Result<Vec<(usize, Option<u64>)>, ()> {
+    let (_, captures) = meta.split_once("\"captures\": [").ok_or(())?;
+    let (captures, _) = captures.split_once(']').ok_or(())?;
+    captures
+        .split('}')
+        .filter(|f| f.contains('{'))
+        .map(|capture| {
+            let field = |name: &str| {
+                capture
+                    .split_once(format!("\"{}\":", name).as_str())
+                    .map(|(_, f)| f.split([',', ' ']).find(|f| !f.is_empty()).unwrap_or(""))
+            };
+            let sample_start = field("core:sample_start")
+                .ok_or(())?
+                .parse::<usize>()
+                .map_err(|_| ())?;
+            let frequency = match field("core:frequency") {
+                Some(f) => Some(f.parse::<u64>().map_err(|_| ())?),
+                None => None,
+            };
+            Ok((sample_start, frequency))
+        })
+        .collect()
+}
+
+// The values are numbers and fixed strings, none of them needs escaping
+#[cfg(not(feature = "serde"))]
+fn format_meta(
+    captures: &[(usize, Option<u64>)],
+    num_channels: usize,
+    complex_data: bool,
+    sample_rate: Option<u32>,
+) -> Result<String, ()> {
+    let datatype = if complex_data { "ci16_le" } else { "ri16_le" };
+    let mut global = format!(
+        "    \"core:datatype\": \"{}\",\n    \"core:version\": \"1.0.0\",\n    \"core:num_channels\": {},\n    \"core:hw\": \"PlutoSDR\",\n    \"core:recorder\": \"adi\"",
+        datatype, num_channels
+    );
+    if let Some(rate) = sample_rate {
+        global = format!("{},\n    \"core:sample_rate\": {}", global, rate);
+    }
+
+    let captures = captures
+        .iter()
+        .map(|(sample_start, frequency)| match frequency {
+            Some(frequency) => format!(
+                "    {{ \"core:sample_start\": {}, \"core:frequency\": {} }}",
+                sample_start, frequency
+            ),
+            None => format!("    {{ \"core:sample_start\": {} }}", sample_start),
+        })
+        .collect::<Vec<String>>()
+        .join(",\n");
+
+    Ok(format!(
+        "{{\n  \"global\": {{\n{}\n  }},\n  \"captures\": [\n{}\n  ],\n  \"annotations\": []\n}}\n",
+        global, captures
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> String {
+        let path =
+            std::env::temp_dir().join(format!("adi-file-sink-{}-{}", name, std::process::id()));
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn meta_round_trip() {
+        let captures = vec![
+            (0, Some(2_400_000_000)),
+            (1024, None),
+            (4096, Some(915_000_000)),
+        ];
+        let meta = format_meta(captures.as_slice(), 1, true, Some(30_720_000)).unwrap();
+        assert_eq!(parse_captures(meta.as_str()), Ok(captures));
+        assert_eq!(
+            parse_captures(format_meta(&[], 2, false, None).unwrap().as_str()),
+            Ok(vec![])
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn meta_from_other_recorder() {
+        let meta = r#"{"annotations":[],"captures":[{"core:frequency":2.4e9,"core:sample_start":0,"core:datetime":"2024-01-01T00:00:00Z"},{"core:sample_start":512}],"global":{"core:datatype":"ci16_le","core:description":"a \"quoted\" ] note"}}"#;
+        assert_eq!(
+            parse_captures(meta),
+            Ok(vec![(0, Some(2_400_000_000)), (512, None)])
+        );
+    }
+
+    #[test]
+    fn append_keeps_captures() {
+        let path = temp_path("append");
+        let data = vec![0u8; 4 * 8];
+
+        let mut sink = FileSink::new(path.clone(), datatypes::TxFileFormat::SigMF, false, false);
+        sink.write(data.as_slice(), 1, true, Some(1_000_000), Some(100))
+            .unwrap();
+        sink.write(data.as_slice(), 1, true, Some(1_000_000), Some(200))
+            .unwrap();
+
+        let mut sink = FileSink::new(path.clone(), datatypes::TxFileFormat::SigMF, true, false);
+        sink.write(data.as_slice(), 1, true, Some(1_000_000), Some(300))
+            .unwrap();
+        assert_eq!(sink.get_samples_written(), 24);
+
+        let meta = std::fs::read_to_string(sink.get_meta_path().unwrap()).unwrap();
+        assert_eq!(
+            parse_captures(meta.as_str()),
+            Ok(vec![(0, Some(100)), (8, Some(200)), (16, Some(300))])
+        );
+
+        std::fs::remove_file(sink.get_data_path()).unwrap();
+        std::fs::remove_file(sink.get_meta_path().unwrap()).unwrap();
+    }
+}
@@ -3,8 +3,10 @@ pub mod channel;
 pub mod channel_attr;
 pub mod context;
 pub mod context_manager;
+pub mod dds_tone;
 pub mod device_attr;
 pub mod device_buffer_attr;
 pub mod device_debug_attr;
 pub mod device_trigger;
+pub mod file_sink;
 pub mod traits;
@@ -12,6 +12,11 @@ pub trait Crx {
 pub trait Ctx {
     fn tx_init_channels(&mut self) -> Result<(), ()>;
     fn tx_buffer_push(&mut self, data: Vec<u8>) -> Result<(), ()>;
+    fn tx_buffer_write<T: crate::datatypes::TxSample, I: Iterator<Item = T>>(
+        &mut self,
+        frames: usize,
+        samples: I,
+    ) -> Result<(), ()>;
 }
 
 pub enum DdsValue {
@@ -42,7 +47,7 @@ impl DdsValue {
     pub fn get_string(&self) -> String
     {
         match self {
-            Self::Float(f) => format!("{:.1}", f),
+            Self::Float(f) => format!("{:}", f),
             Self::Bool(b) => (if *b { "1" } else { "0" }).to_owned(),
             Self::Int(i) => format!("{:}", i)
         }
@@ -0,0 +1,196 @@
+use crate::datatypes;
+use crate::error;
+use crate::traits;
+
+// Stop iterating once the residual crystal error is below this
+static XO_TOLERANCE_PPM: f64 = 0.05;
+
+// External is a tone at an absolute RF frequency from an independent source.
+// Loopback plays a DDS tone on TX with the TX LO tuned to the RX LO and
+// expects it back at its baseband offset, over a cable or the air
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum XoReference {
+    External(u64),
+    Loopback { frequency: i32, scale: f32 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct XoCalibration {
+    pub serial: String,
+    pub xo_correction: u64,
+    pub ppm: f64,
+    pub residual_hz: f64,
+}
+
+// Average phase increment between consecutive samples, robust for a single
+// dominant tone and cheap compared to an FFT
+pub fn estimate_frequency(
+    samples: &[datatypes::PlutoComplex],
+    sample_rate: f64,
+) -> Result<f64, ()> {
+    if samples.len() < 2 {
+        return Err(());
+    }
+    let sum = samples
+        .windows(2)
+        .fold(num::complex::Complex::<f64>::new(0.0, 0.0), |acc, f| {
+            let current = num::complex::Complex::<f64>::new(f[1].re as f64, f[1].im as f64);
+            let previous = num::complex::Complex::<f64>::new(f[0].re as f64, f[0].im as f64);
+            acc + current * previous.conj()
+        });
+    Ok(sum.arg() * sample_rate / (2.0 * std::f64::consts::PI))
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct XoCalibrationStore {
+    entries: Vec<(String, u64)>,
+}
+
+impl XoCalibrationStore {
+    pub fn new() -> XoCalibrationStore {
+        XoCalibrationStore { entries: vec![] }
+    }
+
+    pub fn parse(data: &str) -> Result<XoCalibrationStore, ()> {
+        let mut store = XoCalibrationStore::new();
+        for line in data.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (serial, xo_correction) = line.split_once(' ').ok_or(())?;
+            let xo_correction = xo_correction.trim().parse::<u64>().map_err(|_| ())?;
+            store.insert(serial, xo_correction);
+        }
+        Ok(store)
+    }
+
+    // A missing file is an empty store, so the first calibration can create it
+    pub fn load(filename: &str) -> Result<XoCalibrationStore, ()> {
+        match std::fs::read_to_string(filename) {
+            Ok(data) => XoCalibrationStore::parse(data.as_str()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(XoCalibrationStore::new()),
+            Err(_) => Err(()),
+        }
+    }
+
+    pub fn save(&self, filename: &str) -> Result<(), ()> {
+        std::fs::write(filename, self.to_string()).map_err(|_| ())
+    }
+
+    pub fn insert(&mut self, serial: &str, xo_correction: u64) {
+        self.entries.retain(|(f, _)| f != serial);
+        self.entries.push((serial.to_owned(), xo_correction));
+    }
+
+    pub fn get(&self, serial: &str) -> Option<u64> {
+        self.entries
+            .iter()
+            .find(|(f, _)| f == serial)
+            .map(|(_, f)| *f)
+    }
+}
+
+impl std::fmt::Display for XoCalibrationStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# serial xo_correction")?;
+        for (serial, xo_correction) in self.entries.iter() {
+            writeln!(f, "{} {}", serial, xo_correction)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T> traits::XoCalibrate<'a> for T
+where
+    T: traits::AD9364<'a> + traits::RxCore<'a> + traits::DDS<'a>,
+{
+    fn calibrate_xo(
+        &mut self,
+        reference: XoReference,
+        iterations: usize,
+    ) -> Result<XoCalibration, error::AdiError> {
+        let serial = self.get_hw_serial().ok_or(error::AdiError::IioError)?;
+        // The middle of the correction range is the nominal crystal frequency
+        let available = self.get_xo_correction_available()?;
+        let nominal = (available.min + available.max) / 2.0;
+        let mut xo_correction = self.get_xo_correction()?;
+
+        let tx_lo = self.get_tx_lo()?;
+        if let XoReference::Loopback { frequency, scale } = reference {
+            self.set_tx_lo(self.get_rx_lo()?)?;
+            self.dds_single_tone(frequency, scale, 0)?;
+        }
+
+        let mut residual_hz = f64::NAN;
+        let result = (|| {
+            for _ in 0..iterations {
+                let rx_lo = self.get_rx_lo()?;
+                let sample_rate = self.get_sample_rate()? as f64;
+                let expected = match reference {
+                    XoReference::External(frequency) => frequency as f64 - rx_lo as f64,
+                    XoReference::Loopback { frequency, .. } => frequency as f64,
+                };
+                if expected.abs() >= sample_rate / 2.0 {
+                    return Err(error::AdiError::RangeError {
+                        value: expected.to_string(),
+                        available: format!("+/- {}", sample_rate / 2.0),
+                    });
+                }
+
+                // The first buffer may hold samples from before the last correction
+                self.rx_complex()?;
+                let data = self.rx_complex()?;
+                let samples = data.first().ok_or(error::AdiError::IioError)?;
+                let measured = estimate_frequency(samples, sample_rate)?;
+
+                // A crystal running fast pulls the LO up and the received tone down
+                residual_hz = measured - expected;
+                let error = -residual_hz / rx_lo as f64;
+                if error.abs() * 1e6 < XO_TOLERANCE_PPM {
+                    break;
+                }
+                xo_correction = (xo_correction as f64 * (1.0 + error)).round() as u64;
+                self.set_xo_correction(xo_correction)?;
+            }
+            Ok(())
+        })();
+
+        if let XoReference::Loopback { .. } = reference {
+            self.disable_dds()?;
+            self.set_tx_lo(tx_lo)?;
+        }
+        result?;
+
+        Ok(XoCalibration {
+            serial,
+            xo_correction,
+            ppm: (xo_correction as f64 / nominal - 1.0) * 1e6,
+            residual_hz,
+        })
+    }
+
+    fn calibrate_xo_persist(
+        &mut self,
+        reference: XoReference,
+        iterations: usize,
+        filename: &str,
+    ) -> Result<XoCalibration, error::AdiError> {
+        let calibration = self.calibrate_xo(reference, iterations)?;
+        let mut store = XoCalibrationStore::load(filename)?;
+        store.insert(calibration.serial.as_str(), calibration.xo_correction);
+        store.save(filename)?;
+        Ok(calibration)
+    }
+
+    fn apply_xo_calibration(&mut self, filename: &str) -> Result<bool, error::AdiError> {
+        let serial = self.get_hw_serial().ok_or(error::AdiError::IioError)?;
+        match XoCalibrationStore::load(filename)?.get(serial.as_str()) {
+            Some(xo_correction) => {
+                self.set_xo_correction(xo_correction)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}